@@ -0,0 +1,201 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+/// Resolves the target directories of Cargo projects that don't build into their own
+/// `target` folder, as set with `CARGO_TARGET_DIR` or `build.target-dir`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CargoConfig {
+    /// The target directory set with `CARGO_TARGET_DIR`, which applies to every project
+    target_dir: Option<PathBuf>,
+    /// The Cargo home, whose config file applies to every project
+    home: Option<PathBuf>,
+}
+
+impl CargoConfig {
+    /// Reads `CARGO_TARGET_DIR` and `CARGO_HOME` (defaulting to `~/.cargo`), where
+    /// relative paths are resolved against the current directory, like Cargo does.
+    pub fn from_env() -> io::Result<Self> {
+        let current_dir = env::current_dir()?;
+
+        Ok(CargoConfig::new(
+            env::var_os("CARGO_TARGET_DIR"),
+            env::var_os("CARGO_HOME"),
+            env::home_dir(),
+            &current_dir,
+        ))
+    }
+
+    fn new(
+        target_dir: Option<OsString>,
+        cargo_home: Option<OsString>,
+        home: Option<PathBuf>,
+        current_dir: &Path,
+    ) -> Self {
+        let absolute = |path: OsString| current_dir.join(path);
+
+        Self {
+            target_dir: target_dir.filter(|p| !p.is_empty()).map(absolute),
+            home: cargo_home
+                .filter(|p| !p.is_empty())
+                .map(absolute)
+                .or_else(|| home.map(|home| home.join(".cargo"))),
+        }
+    }
+
+    /// Returns the target directory of the project, if it is not the default `target`
+    /// folder inside it.
+    ///
+    /// Like Cargo, this looks for `build.target-dir` in `.cargo/config.toml` in the
+    /// project and every folder above it, where the closest one wins, and then in the
+    /// config file of the Cargo home. Config files that can't be read are skipped.
+    pub fn target_dir(&self, project: &Path) -> Option<PathBuf> {
+        if let Some(target_dir) = &self.target_dir {
+            return Some(target_dir.clone());
+        }
+
+        let target_dir = project
+            .ancestors()
+            .map(|dir| dir.join(".cargo"))
+            .chain(self.home.clone())
+            .find_map(|cargo_dir| CargoConfig::read_target_dir(&cargo_dir))?;
+
+        // the default is not a custom target directory
+        (target_dir != project.join("target")).then_some(target_dir)
+    }
+
+    /// Reads `build.target-dir` from the config file in the `.cargo` folder, resolving
+    /// a relative path against the folder that holds the `.cargo` folder.
+    fn read_target_dir(cargo_dir: &Path) -> Option<PathBuf> {
+        // `config` is the name used before Cargo 1.39, and is read when `config.toml` is missing
+        let content = fs::read_to_string(cargo_dir.join("config.toml"))
+            .or_else(|_| fs::read_to_string(cargo_dir.join("config")))
+            .ok()?;
+
+        let target_dir = CargoConfig::parse_target_dir(&content)?;
+
+        Some(cargo_dir.parent().unwrap_or(cargo_dir).join(target_dir))
+    }
+
+    fn parse_target_dir(content: &str) -> Option<PathBuf> {
+        let table = content.parse::<toml::Table>().ok()?;
+
+        table
+            .get("build")?
+            .get("target-dir")?
+            .as_str()
+            .map(PathBuf::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+
+    use rstest::rstest;
+
+    use crate::cargo_config::CargoConfig;
+    use crate::tests::helpers::test_run::TestRun;
+
+    #[rstest]
+    #[case("[build]\ntarget-dir = \"out\"", Some("out"))]
+    #[case("build.target-dir = \"/tmp/out\"", Some("/tmp/out"))]
+    #[case("[build]\njobs = 4", None)]
+    #[case("[alias]\nb = \"build\"", None)]
+    #[case("build = ", None)]
+    fn test_parse_target_dir(#[case] content: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            CargoConfig::parse_target_dir(content),
+            expected.map(PathBuf::from)
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Some("/shared"),
+        None,
+        Some("/home/user"),
+        "/shared",
+        Some("/home/user/.cargo")
+    )]
+    #[case(
+        Some("relative"),
+        Some("cargo"),
+        None,
+        "/cwd/relative",
+        Some("/cwd/cargo")
+    )]
+    #[case(Some(""), None, None, "", None)]
+    fn test_new(
+        #[case] target_dir: Option<&str>,
+        #[case] cargo_home: Option<&str>,
+        #[case] home: Option<&str>,
+        #[case] expected_target_dir: &str,
+        #[case] expected_home: Option<&str>,
+    ) {
+        let config = CargoConfig::new(
+            target_dir.map(OsString::from),
+            cargo_home.map(OsString::from),
+            home.map(PathBuf::from),
+            Path::new("/cwd"),
+        );
+
+        assert_eq!(
+            config.target_dir,
+            Some(PathBuf::from(expected_target_dir)).filter(|p| !p.as_os_str().is_empty())
+        );
+        assert_eq!(config.home, expected_home.map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_target_dir() {
+        let test_run = TestRun::empty();
+        let root = &test_run.path;
+        let workspace = root.join("workspace");
+        let member = workspace.join("member");
+        let home = root.join("cargo-home");
+
+        for dir in [&member.join(".cargo"), &workspace.join(".cargo"), &home] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+
+        let write = |path: PathBuf, target_dir: &str| {
+            std::fs::write(path, format!("[build]\ntarget-dir = \"{target_dir}\"")).unwrap();
+        };
+
+        let config = CargoConfig {
+            target_dir: None,
+            home: Some(home.clone()),
+        };
+
+        // nothing is configured
+        assert_eq!(config.target_dir(&member), None);
+
+        // the Cargo home is used when nothing closer is configured, relative to its parent
+        write(home.join("config.toml"), "shared");
+        assert_eq!(config.target_dir(&member), Some(root.join("shared")));
+
+        // the legacy name is read too, relative to the folder holding `.cargo`
+        write(workspace.join(".cargo").join("config"), "out");
+        assert_eq!(config.target_dir(&member), Some(workspace.join("out")));
+
+        // the closest config file wins
+        write(member.join(".cargo").join("config.toml"), "/elsewhere");
+        assert_eq!(
+            config.target_dir(&member),
+            Some(PathBuf::from("/elsewhere"))
+        );
+
+        // the default target folder is not custom
+        write(member.join(".cargo").join("config.toml"), "target");
+        assert_eq!(config.target_dir(&member), None);
+
+        // `CARGO_TARGET_DIR` overrides every config file
+        let config = CargoConfig {
+            target_dir: Some(PathBuf::from("/env")),
+            home: Some(home),
+        };
+        assert_eq!(config.target_dir(&member), Some(PathBuf::from("/env")));
+    }
+}
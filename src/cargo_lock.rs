@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The package versions locked in the `Cargo.lock` files of some projects.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CargoLock {
+    packages: HashSet<(String, String)>,
+}
+
+impl CargoLock {
+    /// Reads the `Cargo.lock` files of the projects, which are in the project folders
+    /// or in the folder of their workspace above them.
+    ///
+    /// Returns `None` when none of the projects has a lock file that can be read, in
+    /// which case nothing can be told about their artifacts.
+    pub fn read(projects: &[PathBuf]) -> Option<Self> {
+        let lockfiles = projects
+            .iter()
+            .filter_map(|project| CargoLock::lockfile(project))
+            .collect::<HashSet<_>>();
+
+        let mut packages = HashSet::new();
+        let mut found = false;
+
+        for lockfile in lockfiles {
+            if let Some(mut locked) = fs::read_to_string(lockfile)
+                .ok()
+                .and_then(|content| CargoLock::parse(&content))
+            {
+                packages.extend(locked.packages.drain());
+                found = true;
+            }
+        }
+
+        found.then_some(CargoLock { packages })
+    }
+
+    /// Finds the lock file next to the project, or else next to the manifest of the
+    /// closest workspace above it. The lock files further up belong to other projects.
+    fn lockfile(project: &Path) -> Option<PathBuf> {
+        let root = if project.join("Cargo.lock").is_file() {
+            project
+        } else {
+            project.ancestors().skip(1).find(|dir| {
+                fs::read_to_string(dir.join("Cargo.toml"))
+                    .ok()
+                    .and_then(|content| content.parse::<toml::Table>().ok())
+                    .is_some_and(|manifest| manifest.contains_key("workspace"))
+            })?
+        };
+
+        Some(root.join("Cargo.lock")).filter(|path| path.is_file())
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let table = content.parse::<toml::Table>().ok()?;
+
+        let packages = table
+            .get("package")?
+            .as_array()?
+            .iter()
+            .filter_map(|package| {
+                let name = package.get("name")?.as_str()?;
+                let version = package.get("version")?.as_str()?;
+
+                Some((name.to_string(), version.to_string()))
+            })
+            .collect();
+
+        Some(CargoLock { packages })
+    }
+
+    pub fn is_locked(&self, name: &str, version: &str) -> bool {
+        self.packages
+            .contains(&(name.to_string(), version.to_string()))
+    }
+
+    /// Finds the version of the package from a dep-info file (`.d`) of one of its units,
+    /// which lists its sources. Only the packages from a registry can be told, since
+    /// their sources are in a folder named after the package and its version.
+    pub fn dep_info_version(name: &str, dep_info: &str) -> Option<String> {
+        let (_, sources) = dep_info.lines().next()?.split_once(": ")?;
+        let source = Path::new(sources.split_whitespace().next()?);
+
+        // e.g. `~/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/serde-1.0.228/src/lib.rs`
+        let mut components = source.components();
+        components.find(|c| c.as_os_str() == "registry")?;
+
+        components.find_map(|component| match component {
+            Component::Normal(dir) => {
+                let version = dir.to_str()?.strip_prefix(name)?.strip_prefix('-')?;
+
+                version
+                    .starts_with(|c: char| c.is_ascii_digit())
+                    .then(|| version.to_string())
+            }
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rstest::rstest;
+
+    use crate::cargo_lock::CargoLock;
+    use crate::tests::helpers::test_run::TestRun;
+
+    const LOCKFILE: &str = r#"
+version = 4
+
+[[package]]
+name = "anyhow"
+version = "1.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+dependencies = ["anyhow"]
+"#;
+
+    #[test]
+    fn test_parse() {
+        let lock = CargoLock::parse(LOCKFILE).unwrap();
+
+        assert!(lock.is_locked("anyhow", "1.0.100"));
+        assert!(lock.is_locked("my-app", "0.1.0"));
+        assert!(!lock.is_locked("anyhow", "1.0.99"));
+        assert_eq!(CargoLock::parse("version = 4"), None);
+    }
+
+    #[rstest]
+    #[case(
+        "aho-corasick",
+        "/t/deps/aho_corasick-08ca86f1e2744f25.d: /home/u/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/aho-corasick-1.1.4/src/lib.rs /x.rs\n",
+        Some("1.1.4")
+    )]
+    #[case(
+        "anyhow",
+        "/t/build/anyhow-7f0d6b033cdf0766/build_script_build-7f0d6b033cdf0766.d: /home/u/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/anyhow-1.0.100/build.rs",
+        Some("1.0.100")
+    )]
+    #[case(
+        "serde",
+        "/t/deps/serde-0123456789abcdef.d: /home/u/.cargo/registry/src/index/serde_json-1.0.1/src/lib.rs",
+        None
+    )]
+    #[case(
+        "my-app",
+        "/t/deps/my_app-0123456789abcdef.d: /work/my-app-2/src/main.rs",
+        None
+    )]
+    #[case("my-app", "", None)]
+    fn test_dep_info_version(
+        #[case] name: &str,
+        #[case] dep_info: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            CargoLock::dep_info_version(name, dep_info),
+            expected.map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_read() {
+        let test_run = TestRun::empty();
+        let root = &test_run.path;
+        let workspace = root.join("workspace");
+        let member = workspace.join("member");
+        let app = root.join("app");
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::create_dir_all(&app).unwrap();
+
+        // the lock file of another project above is not used
+        std::fs::write(root.join("Cargo.lock"), LOCKFILE).unwrap();
+        std::fs::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]",
+        )
+        .unwrap();
+
+        assert_eq!(CargoLock::read(std::slice::from_ref(&member)), None);
+        assert_eq!(CargoLock::read(std::slice::from_ref(&app)), None);
+
+        // the lock file of the workspace is used for its members
+        std::fs::write(workspace.join("Cargo.lock"), LOCKFILE).unwrap();
+        let lock = CargoLock::read(&[member, PathBuf::from("/nonexistent")]).unwrap();
+
+        assert!(lock.is_locked("anyhow", "1.0.100"));
+
+        // and the lock file of the project itself
+        std::fs::write(
+            app.join("Cargo.lock"),
+            "[[package]]\nname = \"app\"\nversion = \"0.2.0\"",
+        )
+        .unwrap();
+        let lock = CargoLock::read(&[app]).unwrap();
+
+        assert!(lock.is_locked("app", "0.2.0"));
+        assert!(!lock.is_locked("anyhow", "1.0.100"));
+    }
+}
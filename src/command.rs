@@ -1,7 +1,9 @@
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{fmt, io, str};
 
-use clap::{Parser, ValueEnum, arg, command};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Parser)]
 #[command(name = "cargo", bin_name = "cargo")]
@@ -9,35 +11,290 @@ pub enum Command {
     /// Recursively finds and optionally wipes all "target" (Rust),
     /// "node_modules" (Node), or ".terraform" (Terraform) folders that
     /// are found in the current path.
+    /// Several languages (or `all`) can be targeted in a single run.
     /// Add the `-w` flag to wipe all folders found. USE WITH CAUTION!
     Wipe(Args),
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Default, Parser)]
 #[command(
     version = env!("CARGO_PKG_VERSION"),
     bin_name = "cargo",
     help_template = "{before-help}{name} {version}\n{author-with-newline}{about-with-newline}\n{usage-heading} {usage}\n\n{all-args}{after-help}",
+    args_conflicts_with_subcommands = true,
 )]
 pub struct Args {
-    /// Language to target
-    pub language: LanguageEnum,
+    #[command(subcommand)]
+    pub command: Option<WipeCommand>,
+    /// Languages to target. Defaults to the ones in the config file
+    #[arg(num_args = 1..)]
+    pub languages: Vec<LanguageEnum>,
     /// Caution! If set it will wipe all folders found! Unset by default
     #[arg(short, long)]
     pub wipe: bool,
-    /// Absolute paths to ignore
+    /// Ask for confirmation before wiping each folder. Requires a terminal
+    #[arg(long, requires = "wipe")]
+    pub interactive: bool,
+    /// Browse the folders found in a full-screen list and pick the ones to wipe
+    #[arg(long, conflicts_with = "interactive")]
+    pub tui: bool,
+    /// Move the folders to the trash instead of deleting them
+    #[arg(long)]
+    pub trash: bool,
+    /// Paths or gitignore-style glob patterns to ignore. Relative paths are
+    /// resolved against the searched path and names match at any depth
     #[arg(short, long, value_parser)]
     pub ignores: Vec<PathBuf>,
+    /// Paths to search in. Can be given multiple times. Defaults to the current directory
+    #[arg(short, long = "path", value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+    /// Paths to search in, given after `--`
+    #[arg(last = true, value_name = "PATHS")]
+    pub roots: Vec<PathBuf>,
+    /// Search inside symlinked folders, visiting every folder once. Symlinks are
+    /// never wiped, nor anything reached through them
+    #[arg(long)]
+    pub follow_symlinks: bool,
+    /// Don't search inside other filesystems than the one of each searched path
+    #[arg(short = 'x', long)]
+    pub one_file_system: bool,
+    /// Only search this many levels deep, where the folders in a searched path are at depth 1
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+    /// Only wipe folders at least this many levels deep, e.g. 2 to skip the ones in a searched path
+    #[arg(long, value_name = "N")]
+    pub min_depth: Option<usize>,
+    /// Only wipe folders whose contents were not used in the given period (e.g. 12h, 30d, 8w)
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub older_than: Option<Duration>,
+    /// Only wipe folders of at least the given size (e.g. 500M, 1.5G), which is the apparent
+    /// size of their files
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub min_size: Option<usize>,
+    /// Only wipe the given number of largest folders, by the apparent size of their files
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+    /// Only wipe these parts of the Rust "target" folders, keeping the rest
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "KINDS",
+        conflicts_with = "rust_keep"
+    )]
+    pub rust_only: Vec<RustArtifactEnum>,
+    /// Keep these parts of the Rust "target" folders, wiping the rest
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "KINDS")]
+    pub rust_keep: Vec<RustArtifactEnum>,
+    /// Only wipe the Rust artifacts built by toolchains that are no longer installed,
+    /// or by any other compiler than the one that last built each "target" folder
+    #[arg(long, value_enum, value_name = "MODE", conflicts_with_all = ["rust_only", "rust_keep"])]
+    pub rust_toolchains: Option<StaleToolchainEnum>,
+    /// Only wipe the least recently used Rust artifacts until each "target" folder takes
+    /// up at most the given space on disk (e.g. 5G)
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = parse_size,
+        conflicts_with_all = ["rust_only", "rust_keep", "rust_toolchains"]
+    )]
+    pub rust_max_size: Option<usize>,
+    /// Only wipe the Rust artifacts of the dependency versions that are no longer in the
+    /// Cargo.lock of the projects
+    #[arg(
+        long,
+        conflicts_with_all = ["rust_only", "rust_keep", "rust_toolchains", "rust_max_size"]
+    )]
+    pub rust_unlocked: bool,
+    /// Fail without wiping anything if a folder or file can't be read while searching
+    #[arg(long)]
+    pub strict: bool,
+    /// Don't wipe anything, and exit with code 6 if more space than --max-reclaimable can be reclaimed
+    #[arg(long, requires = "max_reclaimable", conflicts_with_all = ["wipe", "tui"])]
+    pub check: bool,
+    /// The most space on disk that can be reclaimed before --check fails (e.g. 5G), which
+    /// can be less than the apparent size used by --min-size and --top
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, requires = "check")]
+    pub max_reclaimable: Option<usize>,
+    /// Output format [default: text]
+    #[arg(long, value_enum)]
+    pub format: Option<FormatEnum>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WipeCommand {
+    /// Manage the config file at `$XDG_CONFIG_HOME/cargo-wipe/config.toml`
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the settings that are used when no arguments override them
+    Show,
+}
+
+/// Parses a period given as a number followed by a unit:
+/// `s` (seconds), `m` (minutes), `h` (hours), `d` (days), or `w` (weeks).
+pub fn parse_age(value: &str) -> io::Result<Duration> {
+    let value = value.trim();
+    let error = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Valid ages are a number followed by s | m | h | d | w (e.g. 30d)",
+        )
+    };
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(error)?;
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<u64>().map_err(|_| error())?;
+
+    let seconds = match unit.to_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(error()),
+    };
+
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(error)
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, ValueEnum)]
+/// Parses a size given as a number optionally followed by a binary unit:
+/// `K`, `M`, `G`, or `T`, with an optional `B` or `iB` suffix (e.g. `500M`, `1.5GiB`).
+pub fn parse_size(value: &str) -> io::Result<usize> {
+    let value = value.trim();
+    let error = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Valid sizes are a number followed by an optional K | M | G | T (e.g. 500M)",
+        )
+    };
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().map_err(|_| error())?;
+
+    let unit = unit.trim().to_uppercase();
+    let unit = unit
+        .strip_suffix("IB")
+        .or_else(|| unit.strip_suffix('B'))
+        .unwrap_or(&unit);
+
+    let exponent = match unit {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        _ => return Err(error()),
+    };
+
+    let size = number * 1024_f64.powi(exponent);
+
+    if size.is_finite() && size < usize::MAX as f64 {
+        Ok(size as usize)
+    } else {
+        Err(error())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LanguageEnum {
+    All,
     Node,
     Rust,
     Terraform,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl LanguageEnum {
+    /// Expands `all` into every supported language and removes duplicates,
+    /// preserving the order in which the languages were given.
+    pub fn expand(languages: &[LanguageEnum]) -> Vec<LanguageEnum> {
+        let mut expanded = Vec::new();
+
+        for language in languages {
+            let languages = match language {
+                LanguageEnum::All => vec![
+                    LanguageEnum::Node,
+                    LanguageEnum::Rust,
+                    LanguageEnum::Terraform,
+                ],
+                language => vec![language.clone()],
+            };
+
+            for language in languages {
+                if !expanded.contains(&language) {
+                    expanded.push(language);
+                }
+            }
+        }
+
+        expanded
+    }
+
+    /// The folders searched for the language, which are all of them for `all`.
+    pub fn directories(&self) -> &'static [DirectoryEnum] {
+        match self {
+            LanguageEnum::All => &[
+                DirectoryEnum::NodeModules,
+                DirectoryEnum::Target,
+                DirectoryEnum::Terraform,
+            ],
+            LanguageEnum::Node => &[DirectoryEnum::NodeModules],
+            LanguageEnum::Rust => &[DirectoryEnum::Target],
+            LanguageEnum::Terraform => &[DirectoryEnum::Terraform],
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatEnum {
+    /// Colored, human-readable text
+    #[default]
+    Text,
+    /// A versioned JSON document, written once the run is complete
+    Json,
+}
+
+/// The parts of a Rust `target` folder that can be wiped on their own, for the host
+/// and for every target triple.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum RustArtifactEnum {
+    /// The incremental compilation caches of every profile
+    Incremental,
+    /// The output of the `debug` profile
+    Debug,
+    /// The output of the `release` profile
+    Release,
+    /// The documentation built with `cargo doc`
+    Doc,
+    /// The packages built with `cargo package`
+    Package,
+    /// The output of the build scripts of every profile
+    BuildScripts,
+}
+
+/// Which compilers are stale, when only wiping the Rust artifacts they built.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum StaleToolchainEnum {
+    /// The compilers of the toolchains that are no longer installed with rustup
+    Uninstalled,
+    /// Every compiler but the one that last built the "target" folder
+    NotCurrent,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum DirectoryEnum {
     NodeModules,
     Target,
@@ -49,12 +306,13 @@ impl str::FromStr for LanguageEnum {
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value.to_lowercase().trim() {
+            "all" => Ok(LanguageEnum::All),
             "node" => Ok(LanguageEnum::Node),
             "rust" => Ok(LanguageEnum::Rust),
             "terraform" => Ok(LanguageEnum::Terraform),
             _ => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "Valid options are: node | rust | terraform",
+                "Valid options are: all | node | rust | terraform",
             )),
         }
     }
@@ -63,6 +321,7 @@ impl str::FromStr for LanguageEnum {
 impl fmt::Display for LanguageEnum {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            LanguageEnum::All => write!(f, "all"),
             LanguageEnum::Node => write!(f, "node"),
             LanguageEnum::Rust => write!(f, "rust"),
             LanguageEnum::Terraform => write!(f, "terraform"),
@@ -70,22 +329,21 @@ impl fmt::Display for LanguageEnum {
     }
 }
 
-impl From<LanguageEnum> for DirectoryEnum {
-    fn from(language: LanguageEnum) -> Self {
-        match language {
-            LanguageEnum::Node => DirectoryEnum::NodeModules,
-            LanguageEnum::Rust => DirectoryEnum::Target,
-            LanguageEnum::Terraform => DirectoryEnum::Terraform,
+impl From<&DirectoryEnum> for LanguageEnum {
+    fn from(directory: &DirectoryEnum) -> Self {
+        match directory {
+            DirectoryEnum::NodeModules => LanguageEnum::Node,
+            DirectoryEnum::Target => LanguageEnum::Rust,
+            DirectoryEnum::Terraform => LanguageEnum::Terraform,
         }
     }
 }
 
-impl From<&LanguageEnum> for DirectoryEnum {
-    fn from(language: &LanguageEnum) -> Self {
-        match language {
-            LanguageEnum::Node => DirectoryEnum::NodeModules,
-            LanguageEnum::Rust => DirectoryEnum::Target,
-            LanguageEnum::Terraform => DirectoryEnum::Terraform,
+impl fmt::Display for FormatEnum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatEnum::Text => write!(f, "text"),
+            FormatEnum::Json => write!(f, "json"),
         }
     }
 }
@@ -102,13 +360,14 @@ impl fmt::Display for DirectoryEnum {
 
 #[cfg(test)]
 mod tests {
-    use std::{io, str::FromStr};
+    use std::{io, str::FromStr, time::Duration};
 
     use rstest::rstest;
 
-    use crate::command::{DirectoryEnum, LanguageEnum};
+    use crate::command::{DirectoryEnum, LanguageEnum, parse_age, parse_size};
 
     #[rstest]
+    #[case("all", LanguageEnum::All)]
     #[case("node", LanguageEnum::Node)]
     #[case("rust", LanguageEnum::Rust)]
     #[case("terraform", LanguageEnum::Terraform)]
@@ -131,11 +390,12 @@ mod tests {
         assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
         assert_eq!(
             err.to_string(),
-            "Valid options are: node | rust | terraform"
+            "Valid options are: all | node | rust | terraform"
         );
     }
 
     #[rstest]
+    #[case(LanguageEnum::All, "all")]
     #[case(LanguageEnum::Node, "node")]
     #[case(LanguageEnum::Rust, "rust")]
     #[case(LanguageEnum::Terraform, "terraform")]
@@ -144,15 +404,42 @@ mod tests {
     }
 
     #[rstest]
-    #[case(LanguageEnum::Node, DirectoryEnum::NodeModules)]
-    #[case(LanguageEnum::Rust, DirectoryEnum::Target)]
-    #[case(LanguageEnum::Terraform, DirectoryEnum::Terraform)]
+    #[case(LanguageEnum::Node, vec![DirectoryEnum::NodeModules])]
+    #[case(LanguageEnum::Rust, vec![DirectoryEnum::Target])]
+    #[case(LanguageEnum::Terraform, vec![DirectoryEnum::Terraform])]
+    #[case(
+        LanguageEnum::All,
+        vec![DirectoryEnum::NodeModules, DirectoryEnum::Target, DirectoryEnum::Terraform]
+    )]
     fn language_enum_to_directory_enum(
         #[case] language_enum: LanguageEnum,
-        #[case] expected_directory_enum: DirectoryEnum,
+        #[case] expected_directories: Vec<DirectoryEnum>,
+    ) {
+        assert_eq!(language_enum.directories(), expected_directories);
+    }
+
+    #[rstest]
+    #[case(vec![LanguageEnum::Rust], vec![LanguageEnum::Rust])]
+    #[case(vec![LanguageEnum::Rust, LanguageEnum::Node], vec![LanguageEnum::Rust, LanguageEnum::Node])]
+    #[case(vec![LanguageEnum::Rust, LanguageEnum::Rust], vec![LanguageEnum::Rust])]
+    #[case(vec![LanguageEnum::All], vec![LanguageEnum::Node, LanguageEnum::Rust, LanguageEnum::Terraform])]
+    #[case(vec![LanguageEnum::Rust, LanguageEnum::All], vec![LanguageEnum::Rust, LanguageEnum::Node, LanguageEnum::Terraform])]
+    fn language_enum_expand(
+        #[case] languages: Vec<LanguageEnum>,
+        #[case] expected_languages: Vec<LanguageEnum>,
+    ) {
+        assert_eq!(LanguageEnum::expand(&languages), expected_languages);
+    }
+
+    #[rstest]
+    #[case(DirectoryEnum::NodeModules, LanguageEnum::Node)]
+    #[case(DirectoryEnum::Target, LanguageEnum::Rust)]
+    #[case(DirectoryEnum::Terraform, LanguageEnum::Terraform)]
+    fn directory_enum_to_language_enum(
+        #[case] directory_enum: DirectoryEnum,
+        #[case] expected_language_enum: LanguageEnum,
     ) {
-        let directory_enum: DirectoryEnum = language_enum.into();
-        assert_eq!(directory_enum, expected_directory_enum);
+        assert_eq!(LanguageEnum::from(&directory_enum), expected_language_enum);
     }
 
     #[rstest]
@@ -165,4 +452,54 @@ mod tests {
     ) {
         assert_eq!(directory_enum.to_string(), directory_string);
     }
+
+    #[rstest]
+    #[case("45s", 45)]
+    #[case("10m", 10 * 60)]
+    #[case("12h", 12 * 60 * 60)]
+    #[case("30d", 30 * 24 * 60 * 60)]
+    #[case("2W", 2 * 7 * 24 * 60 * 60)]
+    #[case(" 0d ", 0)]
+    fn age_string_to_duration(#[case] age_string: &str, #[case] seconds: u64) {
+        assert_eq!(parse_age(age_string).unwrap(), Duration::from_secs(seconds));
+    }
+
+    #[rstest]
+    #[case("30")]
+    #[case("d")]
+    #[case("-1d")]
+    #[case("1.5d")]
+    #[case("30y")]
+    #[case("99999999999999999999d")]
+    fn age_string_to_duration_error(#[case] age_string: &str) {
+        let err = parse_age(age_string).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    #[case("0", 0)]
+    #[case("512", 512)]
+    #[case("512B", 512)]
+    #[case("10K", 10 * 1024)]
+    #[case("500M", 500 * 1024_usize.pow(2))]
+    #[case("500mb", 500 * 1024_usize.pow(2))]
+    #[case("1.5G", 1024_usize.pow(3) * 3 / 2)]
+    #[case("2 GiB", 2 * 1024_usize.pow(3))]
+    #[case("1T", 1024_usize.pow(4))]
+    fn size_string_to_bytes(#[case] size_string: &str, #[case] bytes: usize) {
+        assert_eq!(parse_size(size_string).unwrap(), bytes);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("M")]
+    #[case("-1M")]
+    #[case("1.2.3M")]
+    #[case("5P")]
+    fn size_string_to_bytes_error(#[case] size_string: &str) {
+        let err = parse_size(size_string).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
 }
@@ -0,0 +1,280 @@
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use std::{env, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::command::{FormatEnum, LanguageEnum, parse_age, parse_size};
+
+/// The defaults read from `$XDG_CONFIG_HOME/cargo-wipe/config.toml`, which are
+/// overridden by the arguments given on the command line.
+///
+/// ```toml
+/// languages = ["rust", "node"]
+/// paths = ["~/projects"]
+/// ignores = ["~/projects/keep-me", "*-legacy"]
+/// older-than = "30d"
+/// min-size = "500M"
+/// format = "text"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// The file the settings were read from, if it exists
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<LanguageEnum>,
+    pub paths: Vec<PathBuf>,
+    /// Added to the ones given on the command line, rather than replaced by them
+    pub ignores: Vec<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<FormatEnum>,
+}
+
+impl Config {
+    /// Loads the config file, or the defaults if there is none.
+    pub fn load() -> io::Result<Self> {
+        let Some(path) = Config::path(env::var_os("XDG_CONFIG_HOME"), env::home_dir()) else {
+            return Ok(Config::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Config::parse(&content, &path),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(io::Error::new(e.kind(), format!("{}: {e}", path.display()))),
+        }
+    }
+
+    /// Parses and validates the contents of the config file at the given path.
+    pub fn parse(content: &str, path: &Path) -> io::Result<Self> {
+        let error = |e: &dyn std::fmt::Display| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: {e}", path.display()),
+            )
+        };
+
+        let config = Config {
+            source: Some(path.to_path_buf()),
+            ..toml::from_str(content).map_err(|e| error(&e))?
+        };
+
+        config.older_than().map_err(|e| error(&e))?;
+        config.min_size().map_err(|e| error(&e))?;
+
+        Ok(config)
+    }
+
+    fn path(xdg_config_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+        // relative paths are invalid and must be ignored, as per the XDG base directory spec
+        let config_home = xdg_config_home
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| home.map(|home| home.join(".config")))?;
+
+        Some(config_home.join("cargo-wipe").join("config.toml"))
+    }
+
+    /// The paths to search in, with a leading `~` expanded to the home directory. Relative
+    /// paths are resolved against the folder of the config file, so that they don't depend
+    /// on where `cargo wipe` runs.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let dir = self.source.as_deref().and_then(Path::parent);
+
+        self.paths
+            .iter()
+            .map(|p| match (Config::expand_home(p), dir) {
+                (path, Some(dir)) if path.is_relative() => dir.join(path),
+                (path, _) => path,
+            })
+            .collect()
+    }
+
+    pub fn older_than(&self) -> io::Result<Option<Duration>> {
+        self.older_than.as_deref().map(parse_age).transpose()
+    }
+
+    pub fn min_size(&self) -> io::Result<Option<usize>> {
+        self.min_size.as_deref().map(parse_size).transpose()
+    }
+
+    /// Writes the settings that are used when no arguments override them.
+    pub fn show(&self, stdout: &mut impl io::Write) -> io::Result<()> {
+        let paths = if self.paths.is_empty() {
+            vec![env::current_dir()?]
+        } else {
+            self.paths()
+        };
+
+        let effective = Config {
+            paths,
+            format: Some(self.format.unwrap_or_default()),
+            ..self.clone()
+        };
+
+        match &self.source {
+            Some(source) => writeln!(stdout, "# Read from {}", source.display())?,
+            None => writeln!(stdout, "# No config file found, so these are the defaults")?,
+        }
+
+        let content = toml::to_string(&effective).map_err(io::Error::other)?;
+        write!(stdout, "{content}")?;
+
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn expand_home(path: &Path) -> PathBuf {
+        let mut components = path.components();
+
+        match (components.next(), env::home_dir()) {
+            (Some(Component::Normal(first)), Some(home)) if first == "~" => {
+                home.join(components.as_path())
+            }
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use rstest::rstest;
+
+    use crate::command::{FormatEnum, LanguageEnum};
+    use crate::config::Config;
+
+    #[rstest]
+    #[case(Some("/xdg"), Some("/home/user"), Some("/xdg/cargo-wipe/config.toml"))]
+    #[case(
+        None,
+        Some("/home/user"),
+        Some("/home/user/.config/cargo-wipe/config.toml")
+    )]
+    #[case(
+        Some("relative"),
+        Some("/home/user"),
+        Some("/home/user/.config/cargo-wipe/config.toml")
+    )]
+    #[case(None, None, None)]
+    fn test_path(
+        #[case] xdg_config_home: Option<&str>,
+        #[case] home: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            Config::path(xdg_config_home.map(OsString::from), home.map(PathBuf::from)),
+            expected.map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let content = r#"
+languages = ["rust", "node"]
+paths = ["/work"]
+ignores = ["*-legacy"]
+older-than = "30d"
+min-size = "500M"
+format = "json"
+"#;
+
+        let config = Config::parse(content, Path::new("config.toml")).unwrap();
+
+        assert_eq!(config.source, Some(PathBuf::from("config.toml")));
+        assert_eq!(
+            config.languages,
+            vec![LanguageEnum::Rust, LanguageEnum::Node]
+        );
+        assert_eq!(config.paths(), vec![PathBuf::from("/work")]);
+        assert_eq!(config.ignores, vec![PathBuf::from("*-legacy")]);
+        assert_eq!(
+            config.older_than().unwrap(),
+            Some(Duration::from_secs(30 * 24 * 60 * 60))
+        );
+        assert_eq!(config.min_size().unwrap(), Some(500 * 1024 * 1024));
+        assert_eq!(config.format, Some(FormatEnum::Json));
+    }
+
+    #[rstest]
+    #[case("languages = [\"cobol\"]")]
+    #[case("older-than = \"30y\"")]
+    #[case("min-size = \"big\"")]
+    #[case("unknown = true")]
+    #[case("paths = ")]
+    fn test_parse_error(#[case] content: &str) {
+        let error = Config::parse(content, Path::new("config.toml")).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(error.to_string().starts_with("config.toml: "));
+    }
+
+    #[test]
+    fn test_paths_expand_home() {
+        let Some(home) = std::env::home_dir() else {
+            return;
+        };
+
+        let config = Config {
+            paths: vec![PathBuf::from("~/projects"), PathBuf::from("/work/~")],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.paths(),
+            vec![home.join("projects"), PathBuf::from("/work/~")]
+        );
+    }
+
+    #[test]
+    fn test_paths_relative_to_source() {
+        let config = Config {
+            source: Some(PathBuf::from("/home/user/.config/cargo-wipe/config.toml")),
+            paths: vec![PathBuf::from("../../projects"), PathBuf::from("/work")],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.paths(),
+            vec![
+                PathBuf::from("/home/user/.config/cargo-wipe/../../projects"),
+                PathBuf::from("/work")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_show() {
+        let config = Config {
+            source: Some(PathBuf::from("/home/user/.config/cargo-wipe/config.toml")),
+            languages: vec![LanguageEnum::Rust],
+            paths: vec![PathBuf::from("/work")],
+            older_than: Some(String::from("30d")),
+            ..Config::default()
+        };
+
+        let mut output = Vec::new();
+        config.show(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(
+            output,
+            r#"# Read from /home/user/.config/cargo-wipe/config.toml
+languages = ["rust"]
+paths = ["/work"]
+ignores = []
+older-than = "30d"
+format = "text"
+"#
+        );
+    }
+}
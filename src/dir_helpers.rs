@@ -1,17 +1,267 @@
 use num_format::{Locale, ToFormattedString};
 use number_prefix::NumberPrefix;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 use std::{fs, io};
 
-use crate::command::{DirectoryEnum, LanguageEnum};
+use crate::cargo_config::CargoConfig;
+use crate::command::DirectoryEnum;
+use crate::ignore_matcher::IgnoreMatcher;
 
-pub type PathsResult = io::Result<Vec<Result<String, io::Error>>>;
+/// The file that protects a folder from being wiped, when it is placed inside it
+/// or next to it, in the project folder.
+pub const KEEP_MARKER: &str = ".cargo-wipe-keep";
+
+#[derive(Debug)]
+pub struct PathToDelete {
+    pub path: String,
+    pub directory: DirectoryEnum,
+    pub ignored: bool,
+    /// Whether the folder or its project folder holds a `.cargo-wipe-keep` file
+    pub protected: bool,
+    /// Whether the folder is reached through a symlink with `--follow-symlinks`, in
+    /// which case it is never wiped
+    pub symlinked: bool,
+    /// The Cargo projects that build into the folder, when it is a custom target directory
+    pub projects: Vec<PathBuf>,
+    /// The folders inside it to wipe instead of the whole folder, with `--rust-only` or
+    /// `--rust-keep`, in which case `dir_info` only counts these
+    pub parts: Option<Vec<PartToDelete>>,
+    pub dir_info: io::Result<DirInfo>,
+}
+
+/// Folders or files wiped on their own, inside a folder that is partly kept.
+#[derive(Debug)]
+pub struct PartToDelete {
+    /// What the part holds, e.g. `debug/incremental` or the compiler that built it
+    pub name: String,
+    pub paths: Vec<PathBuf>,
+    pub dir_info: io::Result<DirInfo>,
+}
+
+/// Identifies a folder regardless of the path it is reached through.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum DirId {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Path(PathBuf),
+}
+
+impl DirId {
+    #[cfg(unix)]
+    fn new(_path: &Path, data: &fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        Some(DirId::Inode(data.dev(), data.ino()))
+    }
+
+    #[cfg(not(unix))]
+    fn new(path: &Path, _data: &fs::Metadata) -> Option<Self> {
+        fs::canonicalize(path).ok().map(DirId::Path)
+    }
+}
+
+/// The hard-linked files seen while sizing folders, by device and inode.
+///
+/// Sharing it while sizing several folders counts every file in only one of them.
+#[derive(Debug, Default)]
+pub struct Hardlinks(Mutex<HashMap<(u64, u64), Hardlink>>);
+
+#[derive(Debug, Clone, Copy)]
+struct Hardlink {
+    /// How many of the links were seen
+    seen: u64,
+    nlink: u64,
+    disk_size: usize,
+}
+
+impl Hardlinks {
+    /// Returns `true` for the last link to the file, once all its links were seen.
+    /// Returns `false` for the other links, and for every link of a file that has
+    /// links elsewhere, since wiping the ones seen doesn't free the file.
+    #[cfg(unix)]
+    fn is_last(&self, data: &fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        if data.nlink() <= 1 {
+            return true;
+        }
+
+        let mut seen = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let link = seen.entry((data.dev(), data.ino())).or_insert(Hardlink {
+            seen: 0,
+            nlink: data.nlink(),
+            disk_size: DirInfo::allocated_size(data),
+        });
+        link.seen += 1;
+
+        link.seen == link.nlink
+    }
+
+    #[cfg(not(unix))]
+    fn is_last(&self, _data: &fs::Metadata) -> bool {
+        true
+    }
+
+    /// Adds the links seen while sizing a folder on its own, and returns the space
+    /// of the files whose last link is among them. Those files were not counted in
+    /// the folder, since some of their links are outside it.
+    fn merge(&self, other: Hardlinks) -> usize {
+        let other = other.0.into_inner().unwrap_or_else(|e| e.into_inner());
+        let mut seen = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        other
+            .into_iter()
+            .filter(|(_, link)| link.seen < link.nlink)
+            .map(|(id, link)| {
+                let merged = seen.entry(id).or_insert(Hardlink { seen: 0, ..link });
+                merged.seen += link.seen;
+
+                if merged.seen == merged.nlink {
+                    merged.disk_size
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+/// How a path is searched.
+#[derive(Debug)]
+pub struct SearchOptions<'a> {
+    pub directories: &'a [DirectoryEnum],
+    pub ignores: &'a IgnoreMatcher,
+    pub follow_symlinks: bool,
+    /// Don't search inside other filesystems than the one of the searched path
+    pub one_file_system: bool,
+    /// Mount points that are never searched, such as the ones of pseudo filesystems
+    pub skipped_mounts: &'a HashSet<PathBuf>,
+    /// Only match folders at least this deep, where the entries of the path are at depth 1
+    pub min_depth: Option<usize>,
+    /// Don't search deeper than this, in which case the totals only include what was searched
+    pub max_depth: Option<usize>,
+    /// Resolves the custom target directories of the Cargo projects found, when searching
+    /// for `target` folders
+    pub cargo_config: Option<&'a CargoConfig>,
+}
+
+/// A folder or file that could not be read while searching or sizing, which
+/// leaves the totals incomplete.
+#[derive(Debug)]
+pub struct ScanError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+/// The errors collected while searching in parallel, which never stop the search.
+#[derive(Debug, Default)]
+struct ScanErrors(Mutex<Vec<ScanError>>);
+
+impl ScanErrors {
+    fn push(&self, path: impl Into<PathBuf>, error: io::Error) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ScanError {
+                path: path.into(),
+                error,
+            });
+    }
+
+    /// Reads the entries of the folder, skipping the ones that fail.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<fs::DirEntry>> {
+        Ok(fs::read_dir(path)?
+            .filter_map(|entry| entry.map_err(|e| self.push(path, e)).ok())
+            .collect())
+    }
+
+    /// Returns the errors sorted by path.
+    fn into_sorted(self) -> Vec<ScanError> {
+        let mut errors = self.0.into_inner().unwrap_or_else(|e| e.into_inner());
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
+        errors
+    }
+}
+
+/// The folders found in a searched path, the totals of everything inside it, and
+/// what could not be read.
+#[derive(Debug)]
+pub struct SearchResult {
+    pub total: DirInfo,
+    pub paths: Vec<PathToDelete>,
+    pub errors: Vec<ScanError>,
+}
+
+/// The folders found in a part of the searched path, and their totals.
+#[derive(Debug)]
+struct Found {
+    total: DirInfo,
+    paths: Vec<PathToDelete>,
+}
+
+impl Default for Found {
+    fn default() -> Self {
+        Self {
+            total: DirInfo::new(0, 0, 0),
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// Whether a found folder will be wiped, or the reason why it is skipped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathStatus {
+    Selected,
+    Ignored,
+    /// Protected with a `.cargo-wipe-keep` file
+    Protected,
+    /// Reached through a symlink with `--follow-symlinks`
+    Symlinked,
+    Recent,
+    Filtered,
+    /// Not confirmed when prompted with `--interactive`
+    Declined,
+}
+
+impl PathStatus {
+    /// Returns `false` for the folders that are never wiped, even when picked in the TUI.
+    pub fn is_selectable(self) -> bool {
+        !matches!(
+            self,
+            PathStatus::Ignored | PathStatus::Protected | PathStatus::Symlinked
+        )
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 pub struct DirInfo {
     pub dir_count: usize,
     pub file_count: usize,
+    /// The apparent size of the files
     pub size: usize,
+    /// The space allocated on disk, which is what wiping frees, counting hard-linked
+    /// files once, and only when all their links are inside the folder
+    pub disk_size: usize,
+    /// The newest modification or access time of the contents, if any
+    pub last_used: Option<SystemTime>,
+}
+
+impl AddAssign for DirInfo {
+    fn add_assign(&mut self, other: Self) {
+        self.dir_count += other.dir_count;
+        self.file_count += other.file_count;
+        self.size += other.size;
+        self.disk_size += other.disk_size;
+        self.last_used = self.last_used.max(other.last_used);
+    }
 }
 
 impl DirInfo {
@@ -20,6 +270,8 @@ impl DirInfo {
             dir_count,
             file_count,
             size,
+            disk_size: size,
+            last_used: None,
         }
     }
 
@@ -33,7 +285,15 @@ impl DirInfo {
     }
 
     pub fn size_formatted_flex(&self) -> String {
-        let np = NumberPrefix::binary(self.size as f64);
+        DirInfo::format_flex(self.size)
+    }
+
+    pub fn disk_size_formatted_flex(&self) -> String {
+        DirInfo::format_flex(self.disk_size)
+    }
+
+    pub fn format_flex(size: usize) -> String {
+        let np = NumberPrefix::binary(size as f64);
 
         match np {
             NumberPrefix::Prefixed(prefix, n) => format!("{n:.2} {prefix}B"),
@@ -41,6 +301,34 @@ impl DirInfo {
         }
     }
 
+    pub fn last_used_formatted(&self, now: SystemTime) -> String {
+        let Some(last_used) = self.last_used else {
+            return String::from("-");
+        };
+
+        let days = now
+            .duration_since(last_used)
+            .map(|d| d.as_secs() / (24 * 60 * 60))
+            .unwrap_or(0);
+
+        match days {
+            0 => String::from("today"),
+            1 => String::from("1 day ago"),
+            days => format!("{} days ago", days.to_formatted_string(&Locale::en)),
+        }
+    }
+
+    /// Returns `true` if the contents were modified or accessed in the given period.
+    pub fn is_used_within(&self, period: Duration, now: SystemTime) -> bool {
+        match self.last_used {
+            Some(last_used) => now
+                .duration_since(last_used)
+                .map(|age| age < period)
+                .unwrap_or(true),
+            None => false,
+        }
+    }
+
     fn is_valid_target(path: PathBuf, directory: &DirectoryEnum) -> bool {
         if directory == &DirectoryEnum::Target {
             let file_path = path.join(".rustc_info.json");
@@ -50,79 +338,469 @@ impl DirInfo {
         true
     }
 
-    pub fn get_paths_to_delete(path: impl Into<PathBuf>, language: &LanguageEnum) -> PathsResult {
-        let directory: DirectoryEnum = language.clone().into();
+    /// Searches the path for the given folders, and sizes everything inside it
+    /// in the same pass, so that every file is only read once.
+    ///
+    /// Ignored folders are not searched, but they are still sized, and an ignored
+    /// folder that is itself a match is still returned and marked as ignored.
+    /// Matches that hold a `.cargo-wipe-keep` file, or whose parent does, are marked
+    /// as protected.
+    ///
+    /// Hard-linked files are counted once in the size on disk of every match, and once
+    /// more in the totals of everything else.
+    ///
+    /// Pseudo filesystems and the other skipped mount points are not searched, and
+    /// neither are other filesystems than the one of the path with `one_file_system`.
+    ///
+    /// With `max_depth`, folders deeper than that are neither searched nor sized, so the
+    /// totals are incomplete.
+    ///
+    /// Symlinks are never matches, and they are only searched when `follow_symlinks`
+    /// is set, in which case every folder is visited once, so that symlink loops end
+    /// and nothing is counted twice. Symlinks to folders inside the searched path are
+    /// skipped, since those folders are found through their own path. The matches
+    /// themselves are always sized without following symlinks, since wiping doesn't
+    /// follow them either.
+    ///
+    /// The folders are searched in parallel, and every match is sized as soon as
+    /// it is found, while the search continues. The paths are returned sorted.
+    ///
+    /// Folders and files that can't be read are skipped and returned as errors, so
+    /// that they don't stop the search of the rest of the path.
+    ///
+    /// With `cargo_config`, the custom target directories of the Cargo projects found
+    /// are returned as well, even when they are outside the path, in which case they
+    /// are added to the totals. Every target directory is returned once, with all the
+    /// projects that build into it.
+    pub fn search(path: impl Into<PathBuf>, options: &SearchOptions) -> SearchResult {
+        struct Search<'a> {
+            root: &'a Path,
+            options: &'a SearchOptions<'a>,
+            /// The device of the root, only set when staying on its filesystem
+            device: Option<u64>,
+            /// The folders visited so far, only tracked when following symlinks
+            visited: Option<Mutex<HashSet<DirId>>>,
+            hardlinks: Hardlinks,
+            errors: ScanErrors,
+            /// The Cargo projects found, only collected with `cargo_config`
+            projects: Mutex<Vec<PathBuf>>,
+        }
+
+        impl Search<'_> {
+            /// Returns `true` if the folder is on another filesystem than the root
+            /// with `--one-file-system`, or is a skipped mount point.
+            fn is_skipped_mount(&self, path: &Path, data: &fs::Metadata) -> bool {
+                self.device
+                    .is_some_and(|device| DirInfo::device(data) != Some(device))
+                    || self.options.skipped_mounts.contains(path)
+            }
+
+            /// Returns `false` if the folder was already visited through another path.
+            fn visit(&self, path: &Path, data: &fs::Metadata) -> bool {
+                match &self.visited {
+                    Some(visited) => match DirId::new(path, data) {
+                        Some(id) => visited.lock().unwrap_or_else(|e| e.into_inner()).insert(id),
+                        None => true,
+                    },
+                    None => true,
+                }
+            }
+        }
 
-        fn walk(dir: io::Result<fs::ReadDir>, directory: &DirectoryEnum) -> PathsResult {
-            let mut dir = match dir {
-                Ok(dir) => dir,
+        /// Searches the folder, whose entries are at the given depth. `symlinked` tells
+        /// whether the folder is reached through a symlink.
+        fn walk(path: &Path, depth: usize, symlinked: bool, search: &Search) -> Found {
+            let files = match search.errors.read_dir(path) {
+                Ok(files) => files,
                 Err(e) => {
-                    return Ok(vec![Err(e)]);
+                    search.errors.push(path, e);
+                    return Found::default();
                 }
             };
 
-            dir.try_fold(
-                Vec::new(),
-                |mut acc: Vec<Result<String, io::Error>>, file| {
-                    let file = file?;
-
-                    let size = match file.metadata() {
-                        Ok(data) if data.is_dir() => {
-                            if file.file_name() == directory.to_string()[..] {
-                                if DirInfo::is_valid_target(file.path(), directory) {
-                                    acc.push(Ok(file.path().display().to_string()));
-                                }
-                            } else {
-                                acc.append(&mut walk(fs::read_dir(file.path()), directory)?);
-                            }
-                            acc
+            files
+                .par_iter()
+                .map(|file| {
+                    let is_symlink = file.file_type().is_ok_and(|t| t.is_symlink());
+
+                    // `DirEntry::metadata` doesn't follow symlinks
+                    let data = if is_symlink && search.visited.is_some() {
+                        fs::metadata(file.path())
+                    } else {
+                        file.metadata()
+                    };
+
+                    let data = match data {
+                        Ok(data) => data,
+                        Err(e) => {
+                            search.errors.push(file.path(), e);
+                            return Found::default();
+                        }
+                    };
+
+                    if !data.is_dir() {
+                        if search.options.cargo_config.is_some() && file.file_name() == "Cargo.toml"
+                        {
+                            search
+                                .projects
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .push(path.to_path_buf());
+                        }
+
+                        return Found {
+                            total: DirInfo::file_info(&data, &search.hardlinks),
+                            paths: Vec::new(),
+                        };
+                    }
+
+                    if is_symlink
+                        && fs::canonicalize(file.path()).is_ok_and(|p| {
+                            p.starts_with(search.root) || search.is_skipped_mount(&p, &data)
+                        })
+                    {
+                        return Found::default();
+                    }
+
+                    if search.is_skipped_mount(&file.path(), &data) {
+                        return Found::default();
+                    }
+
+                    let directory = search
+                        .options
+                        .directories
+                        .iter()
+                        .find(|d| file.file_name() == d.to_string()[..]);
+
+                    // symlinked matches are neither wiped nor searched, like matches aren't
+                    if is_symlink && directory.is_some() {
+                        return Found::default();
+                    }
+
+                    let directory = directory
+                        .filter(|_| search.options.min_depth.is_none_or(|min| depth >= min))
+                        .filter(|d| DirInfo::is_valid_target(file.path(), d));
+
+                    if !search.visit(&file.path(), &data) {
+                        return Found::default();
+                    }
+
+                    let mut result = if let Some(directory) = directory {
+                        let hardlinks = Hardlinks::default();
+                        let dir_info = DirInfo::size(&file.path(), &hardlinks, &search.errors);
+
+                        // the files also linked outside the match only count in the totals
+                        let mut total = dir_info
+                            .as_ref()
+                            .map_or(DirInfo::new(0, 0, 0), |info| *info);
+                        total.disk_size += search.hardlinks.merge(hardlinks);
+
+                        Found {
+                            total,
+                            paths: vec![PathToDelete {
+                                path: file.path().display().to_string(),
+                                directory: *directory,
+                                ignored: search.options.ignores.is_ignored(&file.path()),
+                                protected: DirInfo::is_protected(&file.path()),
+                                symlinked,
+                                projects: Vec::new(),
+                                parts: None,
+                                dir_info,
+                            }],
+                        }
+                    } else if search.options.max_depth.is_some_and(|max| depth >= max) {
+                        Found::default()
+                    } else if !search.options.ignores.is_ignored(&file.path()) {
+                        walk(&file.path(), depth + 1, symlinked || is_symlink, search)
+                    } else if search.options.max_depth.is_some() {
+                        // the totals are incomplete anyway when the depth is limited
+                        Found::default()
+                    } else {
+                        Found {
+                            total: DirInfo::size(&file.path(), &search.hardlinks, &search.errors)
+                                .unwrap_or(DirInfo::new(0, 0, 0)),
+                            paths: Vec::new(),
                         }
-                        _ => acc,
                     };
 
-                    Ok(size)
-                },
+                    result.total.dir_count += 1;
+                    result.total.disk_size += DirInfo::allocated_size(&data);
+
+                    result
+                })
+                .reduce(Found::default, |mut acc, mut result| {
+                    acc.total += result.total;
+                    acc.paths.append(&mut result.paths);
+                    acc
+                })
+        }
+
+        /// Adds the custom target directories of the projects found, or the projects
+        /// to the target folders already found.
+        fn add_target_dirs(found: &mut Found, search: &Search, cargo_config: &CargoConfig) {
+            let projects =
+                std::mem::take(&mut *search.projects.lock().unwrap_or_else(|e| e.into_inner()));
+            let mut target_dirs = BTreeMap::<PathBuf, Vec<PathBuf>>::new();
+
+            for project in projects {
+                let Some(target_dir) = cargo_config
+                    .target_dir(&project)
+                    .and_then(|p| fs::canonicalize(p).ok())
+                else {
+                    continue;
+                };
+
+                // a folder holding the project or the searched path is never wiped
+                if project.starts_with(&target_dir)
+                    || search.root.starts_with(&target_dir)
+                    || !DirInfo::is_valid_target(target_dir.clone(), &DirectoryEnum::Target)
+                {
+                    continue;
+                }
+
+                target_dirs.entry(target_dir).or_default().push(project);
+            }
+
+            for (target_dir, mut projects) in target_dirs {
+                projects.sort();
+                let path = target_dir.display().to_string();
+
+                if let Some(existing) = found.paths.iter_mut().find(|p| p.path == path) {
+                    existing.projects = projects;
+                    continue;
+                }
+
+                let hardlinks = Hardlinks::default();
+                let dir_info = DirInfo::size(&target_dir, &hardlinks, &search.errors);
+
+                // the folders outside the searched path are not part of its totals yet
+                if !target_dir.starts_with(search.root)
+                    && let Ok(dir_info) = &dir_info
+                {
+                    found.total += *dir_info;
+                    found.total.disk_size += search.hardlinks.merge(hardlinks);
+                }
+
+                found.paths.push(PathToDelete {
+                    path,
+                    directory: DirectoryEnum::Target,
+                    ignored: search.options.ignores.is_ignored(&target_dir),
+                    protected: DirInfo::is_protected(&target_dir)
+                        || projects.iter().any(|p| p.join(KEEP_MARKER).exists()),
+                    symlinked: false,
+                    projects,
+                    parts: None,
+                    dir_info,
+                });
+            }
+        }
+
+        let path = path.into();
+
+        if options.ignores.is_ignored(&path) {
+            return SearchResult {
+                total: DirInfo::new(0, 0, 0),
+                paths: Vec::new(),
+                errors: Vec::new(),
+            };
+        }
+
+        let data = fs::metadata(&path).ok();
+
+        let search = Search {
+            root: &path,
+            options,
+            device: data
+                .as_ref()
+                .and_then(DirInfo::device)
+                .filter(|_| options.one_file_system),
+            visited: options.follow_symlinks.then(|| Mutex::new(HashSet::new())),
+            hardlinks: Hardlinks::default(),
+            errors: ScanErrors::default(),
+            projects: Mutex::new(Vec::new()),
+        };
+
+        if let Some(data) = &data {
+            search.visit(&path, data);
+        }
+
+        let mut found = walk(&path, 1, false, &search);
+
+        if let Some(cargo_config) = options.cargo_config {
+            add_target_dirs(&mut found, &search, cargo_config);
+        }
+
+        SearchResult {
+            total: found.total,
+            paths: DirInfo::dedup_paths(found.paths),
+            errors: search.errors.into_sorted(),
+        }
+    }
+
+    #[cfg(unix)]
+    fn device(data: &fs::Metadata) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt;
+
+        Some(data.dev())
+    }
+
+    #[cfg(not(unix))]
+    fn device(_data: &fs::Metadata) -> Option<u64> {
+        None
+    }
+
+    fn is_protected(path: &Path) -> bool {
+        path.join(KEEP_MARKER).exists()
+            || path
+                .parent()
+                .is_some_and(|parent| parent.join(KEEP_MARKER).exists())
+    }
+
+    /// Fails if the folder is a symlink, or is reached through one, since wiping it
+    /// would delete the folder the symlink points to, which may be anywhere.
+    ///
+    /// This expects the searched paths to be canonical, which they are after parsing
+    /// the arguments.
+    pub fn check_no_symlinks(path: &Path) -> io::Result<()> {
+        let error = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "not wiped, since it is reached through a symlink",
             )
+        };
+
+        if fs::symlink_metadata(path)?.is_symlink() {
+            return Err(error());
+        }
+
+        if fs::canonicalize(path)? != path {
+            return Err(error());
+        }
+
+        Ok(())
+    }
+
+    /// Sorts the paths and drops the ones that are equal to or nested inside
+    /// another path, so that every folder is reported and wiped only once.
+    ///
+    /// The paths are sorted by their components, so that the ones nested inside
+    /// a path come right after it, and only the last path kept has to be checked.
+    fn dedup_paths(mut paths: Vec<PathToDelete>) -> Vec<PathToDelete> {
+        paths.sort_by(|a, b| Path::new(&a.path).cmp(Path::new(&b.path)));
+
+        let mut kept: Vec<PathToDelete> = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            if kept
+                .last()
+                .is_some_and(|k| Path::new(&path.path).starts_with(&k.path))
+            {
+                continue;
+            }
+
+            kept.push(path);
         }
 
-        walk(fs::read_dir(path.into()), &directory)
+        kept
     }
 
+    /// When the file was last modified or read. Folders are left out, since reading them
+    /// while searching moves their access time to now.
+    fn last_used(metadata: &fs::Metadata) -> Option<SystemTime> {
+        metadata.modified().ok().max(metadata.accessed().ok())
+    }
+
+    /// Sizes the folder, failing on the first folder or file inside it that can't be read.
+    /// A file is sized on its own.
     pub fn dir_size(path: impl Into<PathBuf>) -> io::Result<DirInfo> {
-        fn walk(dir: io::Result<fs::ReadDir>) -> io::Result<DirInfo> {
-            let mut dir = match dir {
-                Ok(dir) => dir,
-                Err(_) => {
-                    // Return empty stats for unreadable directories instead of failing
-                    return Ok(DirInfo::new(0, 0, 0));
-                }
-            };
+        DirInfo::dir_size_with(path, &Hardlinks::default())
+    }
+
+    /// Sizes the folder like [`DirInfo::dir_size`], sharing the hard-linked files seen
+    /// with the other folders sized with `hardlinks`.
+    pub fn dir_size_with(path: impl Into<PathBuf>, hardlinks: &Hardlinks) -> io::Result<DirInfo> {
+        let path = path.into();
+        let data = fs::symlink_metadata(&path)?;
 
-            dir.try_fold(DirInfo::new(0, 0, 0), |acc, file| {
-                let file = file?;
+        if !data.is_dir() {
+            return Ok(DirInfo::file_info(&data, hardlinks));
+        }
+
+        let errors = ScanErrors::default();
+        let dir_info = DirInfo::size(&path, hardlinks, &errors)?;
+
+        match errors.into_sorted().into_iter().next() {
+            Some(ScanError { error, .. }) => Err(error),
+            None => Ok(dir_info),
+        }
+    }
+
+    /// Sizes the folder, which fails if it can't be read. The folders and files inside
+    /// it that can't be read are skipped and collected.
+    fn size(path: &Path, hardlinks: &Hardlinks, errors: &ScanErrors) -> io::Result<DirInfo> {
+        fn walk(path: &Path, hardlinks: &Hardlinks, errors: &ScanErrors) -> io::Result<DirInfo> {
+            let files = errors.read_dir(path)?;
 
-                let info = match file.metadata() {
+            Ok(files
+                .par_iter()
+                .map(|file| match file.metadata() {
                     // For directories: count 1 directory + recursively count its contents
                     Ok(data) if data.is_dir() => {
-                        let sub_info = walk(fs::read_dir(file.path()))?;
-                        DirInfo::new(1 + sub_info.dir_count, sub_info.file_count, sub_info.size)
+                        let mut info = walk(&file.path(), hardlinks, errors).unwrap_or_else(|e| {
+                            errors.push(file.path(), e);
+                            DirInfo::new(0, 0, 0)
+                        });
+                        info.dir_count += 1;
+                        info.disk_size += DirInfo::allocated_size(&data);
+                        info
                     }
                     // For files: count 1 file and its size in bytes
-                    Ok(data) => DirInfo::new(0, 1, data.len() as usize),
-                    // Skip entries we can't read metadata for
-                    _ => DirInfo::new(0, 0, 0),
-                };
-
-                // Accumulate counts from this entry with running totals
-                Ok(DirInfo::new(
-                    acc.dir_count + info.dir_count,
-                    acc.file_count + info.file_count,
-                    acc.size + info.size,
+                    Ok(data) => DirInfo::file_info(&data, hardlinks),
+                    Err(e) => {
+                        errors.push(file.path(), e);
+                        DirInfo::new(0, 0, 0)
+                    }
+                })
+                // Accumulate counts from every entry with running totals
+                .reduce(
+                    || DirInfo::new(0, 0, 0),
+                    |mut acc, info| {
+                        acc += info;
+                        acc
+                    },
                 ))
-            })
         }
 
-        walk(fs::read_dir(path.into()))
+        walk(path, hardlinks, errors).inspect_err(|e| {
+            errors.push(path, io::Error::new(e.kind(), e.to_string()));
+        })
+    }
+
+    /// Counts a single file, whose allocated size is only counted once all the links
+    /// to a hard-linked file were seen.
+    fn file_info(data: &fs::Metadata, hardlinks: &Hardlinks) -> DirInfo {
+        let mut info = DirInfo::new(0, 1, data.len() as usize);
+        info.disk_size = if hardlinks.is_last(data) {
+            DirInfo::allocated_size(data)
+        } else {
+            0
+        };
+        info.last_used = DirInfo::last_used(data);
+        info
+    }
+
+    /// The space allocated on disk, which is smaller than the length for sparse files.
+    #[cfg(unix)]
+    fn allocated_size(data: &fs::Metadata) -> usize {
+        use std::os::unix::fs::MetadataExt;
+
+        // the blocks are always counted in units of 512 bytes
+        data.blocks() as usize * 512
+    }
+
+    #[cfg(not(unix))]
+    fn allocated_size(data: &fs::Metadata) -> usize {
+        data.len() as usize
     }
 }
 
@@ -139,12 +817,72 @@ mod tests {
     #[case(1024_usize.pow(3), "1.00 GiB")]
     #[case(1024_usize.pow(4), "1.00 TiB")]
     fn test_size_formatted_flex(#[case] size: usize, #[case] output: &str) {
-        let di = DirInfo {
-            dir_count: 0,
-            file_count: 0,
-            size,
-        };
+        let di = DirInfo::new(0, 0, size);
 
         assert_eq!(di.size_formatted_flex(), output);
     }
+
+    #[rstest]
+    #[case(None, "-")]
+    #[case(Some(0), "today")]
+    #[case(Some(60 * 60), "today")]
+    #[case(Some(24 * 60 * 60), "1 day ago")]
+    #[case(Some(3 * 24 * 60 * 60 + 60), "3 days ago")]
+    #[case(Some(1234 * 24 * 60 * 60), "1,234 days ago")]
+    fn test_last_used_formatted(#[case] age: Option<u64>, #[case] output: &str) {
+        let now = SystemTime::now();
+
+        let mut di = DirInfo::new(0, 0, 0);
+        di.last_used = age.map(|age| now - Duration::from_secs(age));
+
+        assert_eq!(di.last_used_formatted(now), output);
+    }
+
+    #[rstest]
+    #[case(None, false)]
+    #[case(Some(0), true)]
+    #[case(Some(29), true)]
+    #[case(Some(30), false)]
+    #[case(Some(31), false)]
+    fn test_is_used_within(#[case] age_days: Option<u64>, #[case] used: bool) {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let mut di = DirInfo::new(0, 0, 0);
+        di.last_used = age_days.map(|age| now - day * age as u32);
+
+        assert_eq!(di.is_used_within(day * 30, now), used);
+    }
+
+    #[test]
+    fn test_dedup_paths() {
+        let path = |path: &str| PathToDelete {
+            path: path.to_string(),
+            directory: DirectoryEnum::Target,
+            ignored: false,
+            protected: false,
+            symlinked: false,
+            projects: Vec::new(),
+            parts: None,
+            dir_info: Ok(DirInfo::new(0, 0, 0)),
+        };
+
+        let paths = vec![
+            path("/work/b/target"),
+            path("/work/a/target/nested/target"),
+            path("/work/a/target"),
+            path("/work/a/target-other"),
+            path("/work/b/target"),
+        ];
+
+        let paths = DirInfo::dedup_paths(paths)
+            .into_iter()
+            .map(|p| p.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec!["/work/a/target", "/work/a/target-other", "/work/b/target"]
+        );
+    }
 }
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The space of a filesystem, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Space {
+    /// The space used by files
+    pub used: u64,
+    /// The space left for unprivileged users, which is what `df` shows as available
+    pub free: u64,
+}
+
+impl Space {
+    /// The percentage of the space that is used, computed the same way as `df` does,
+    /// without the space reserved for the root user.
+    pub fn used_percent(&self) -> f64 {
+        let size = self.used + self.free;
+
+        if size == 0 {
+            0.0
+        } else {
+            self.used as f64 * 100.0 / size as f64
+        }
+    }
+}
+
+/// The free space of a filesystem that holds folders to wipe, measured before
+/// and after wiping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsSpace {
+    pub mount_point: PathBuf,
+    pub before: Space,
+    pub after: Option<Space>,
+}
+
+impl FsSpace {
+    /// Measures the filesystems holding the paths, grouped by mount point.
+    ///
+    /// Filesystems that can't be measured are left out, which is all of them on
+    /// systems without `statvfs`.
+    pub fn measure<'p>(paths: impl IntoIterator<Item = &'p Path>) -> Vec<FsSpace> {
+        let mut spaces = BTreeMap::new();
+
+        for path in paths {
+            let Ok(mount_point) = mount_point(path) else {
+                continue;
+            };
+
+            if spaces.contains_key(&mount_point) {
+                continue;
+            }
+
+            if let Ok(before) = space(&mount_point) {
+                spaces.insert(mount_point, before);
+            }
+        }
+
+        spaces
+            .into_iter()
+            .map(|(mount_point, before)| FsSpace {
+                mount_point,
+                before,
+                after: None,
+            })
+            .collect()
+    }
+
+    /// Measures the filesystem again.
+    pub fn measure_after(&mut self) {
+        self.after = space(&self.mount_point).ok();
+    }
+
+    /// The free space gained since the first measurement, which is negative
+    /// when something else took up more space in the meantime.
+    pub fn freed(&self) -> Option<i64> {
+        self.after
+            .map(|after| after.free as i64 - self.before.free as i64)
+    }
+}
+
+/// Finds the mount point of the filesystem holding the path, as the topmost
+/// ancestor on the same device.
+#[cfg(unix)]
+pub fn mount_point(path: &Path) -> io::Result<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = std::fs::metadata(path)?.dev();
+    let mut mount_point = path;
+
+    for ancestor in path.ancestors().skip(1) {
+        match std::fs::metadata(ancestor) {
+            Ok(data) if data.dev() == device => mount_point = ancestor,
+            _ => break,
+        }
+    }
+
+    Ok(mount_point.to_path_buf())
+}
+
+#[cfg(not(unix))]
+pub fn mount_point(_path: &Path) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Mount points are only supported on Unix-like systems",
+    ))
+}
+
+/// Queries the space of the filesystem holding the path with `statvfs`.
+#[cfg(unix)]
+pub fn space(path: &Path) -> io::Result<Space> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // SAFETY: `stat` is plain data that `statvfs` fills in, and `path` is a valid
+    // C string for the duration of the call
+    let stat = unsafe {
+        let mut stat = std::mem::zeroed::<libc::statvfs>();
+        if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat
+    };
+
+    #[allow(clippy::unnecessary_cast)]
+    let (fragment_size, blocks, blocks_free, blocks_available) = (
+        stat.f_frsize as u64,
+        stat.f_blocks as u64,
+        stat.f_bfree as u64,
+        stat.f_bavail as u64,
+    );
+
+    Ok(Space {
+        used: blocks.saturating_sub(blocks_free) * fragment_size,
+        free: blocks_available * fragment_size,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn space(_path: &Path) -> io::Result<Space> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Free space is only supported on Unix-like systems",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rstest::rstest;
+
+    use crate::disk_space::{FsSpace, Space};
+
+    #[rstest]
+    #[case(0, 0, 0.0)]
+    #[case(25, 75, 25.0)]
+    #[case(100, 0, 100.0)]
+    fn test_used_percent(#[case] used: u64, #[case] free: u64, #[case] percent: f64) {
+        assert_eq!(Space { used, free }.used_percent(), percent);
+    }
+
+    #[rstest]
+    #[case(100, Some(150), Some(50))]
+    #[case(100, Some(90), Some(-10))]
+    #[case(100, None, None)]
+    fn test_freed(#[case] before: u64, #[case] after: Option<u64>, #[case] freed: Option<i64>) {
+        let space = FsSpace {
+            mount_point: PathBuf::from("/"),
+            before: Space {
+                used: 0,
+                free: before,
+            },
+            after: after.map(|free| Space { used: 0, free }),
+        };
+
+        assert_eq!(space.freed(), freed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_measure() {
+        let temp_dir = std::env::temp_dir();
+        let spaces = FsSpace::measure([temp_dir.as_path(), temp_dir.as_path()]);
+
+        // the same filesystem is only measured once
+        assert_eq!(spaces.len(), 1);
+        assert!(temp_dir.starts_with(&spaces[0].mount_point));
+        assert!(spaces[0].before.used + spaces[0].before.free > 0);
+    }
+}
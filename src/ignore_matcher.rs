@@ -0,0 +1,295 @@
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs, io};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+/// Decides which paths are ignored during a search in a root path.
+///
+/// Every pattern follows the gitignore conventions:
+/// - patterns without a `/` (e.g. `legacy` or `*.bak`) match a folder name at any depth;
+/// - patterns with a `/` are anchored, either to the filesystem root when absolute,
+///   or to the searched root when relative (e.g. `apps/web` or `./vendor`);
+/// - a leading `~` is expanded to the home directory;
+/// - `*`, `?`, `[...]`, `{a,b}` and `**` can be used as wildcards.
+///
+/// Paths are compared component by component, so ignoring `/work/foo` does not
+/// ignore `/work/foobar`. Case sensitivity follows the filesystem of the root.
+#[derive(Debug)]
+pub struct IgnoreMatcher {
+    case_insensitive: bool,
+    paths: Vec<PathBuf>,
+    path_globs: Vec<GlobMatcher>,
+    name_globs: Vec<GlobMatcher>,
+}
+
+impl IgnoreMatcher {
+    pub fn new(patterns: &[PathBuf], root: &Path) -> io::Result<Self> {
+        Self::with_case_sensitivity(patterns, root, IgnoreMatcher::is_case_insensitive(root))
+    }
+
+    pub fn with_case_sensitivity(
+        patterns: &[PathBuf],
+        root: &Path,
+        case_insensitive: bool,
+    ) -> io::Result<Self> {
+        let mut matcher = Self {
+            case_insensitive,
+            paths: Vec::new(),
+            path_globs: Vec::new(),
+            name_globs: Vec::new(),
+        };
+
+        for pattern in patterns {
+            let pattern = pattern.to_string_lossy();
+            let pattern = pattern.trim_end_matches(['/', '\\']);
+
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let is_glob = pattern.contains(['*', '?', '[', '{']);
+            let is_anchored = pattern.contains(['/', '\\']) || pattern == "~";
+
+            if !is_anchored {
+                if is_glob {
+                    matcher.name_globs.push(matcher.build_glob(pattern)?);
+                } else {
+                    matcher
+                        .name_globs
+                        .push(matcher.build_glob(&globset::escape(pattern))?);
+                }
+                continue;
+            }
+
+            if is_glob {
+                let root = PathBuf::from(globset::escape(&root.to_string_lossy()));
+                let path = IgnoreMatcher::resolve(pattern, &root, true);
+                matcher
+                    .path_globs
+                    .push(matcher.build_glob(&path.to_string_lossy())?);
+            } else {
+                let path = IgnoreMatcher::resolve(pattern, root, false);
+                matcher.paths.push(matcher.normalize_case(&path));
+            }
+        }
+
+        Ok(matcher)
+    }
+
+    /// Returns `true` if the path matches any of the patterns.
+    ///
+    /// Literal paths also match everything nested inside them, while globs only
+    /// match the path itself, since the ancestors are checked while descending.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if !self.paths.is_empty() {
+            let path = self.normalize_case(path);
+
+            if self.paths.iter().any(|p| path.starts_with(p)) {
+                return true;
+            }
+        }
+
+        if self.path_globs.iter().any(|g| g.is_match(path)) {
+            return true;
+        }
+
+        match path.file_name() {
+            Some(name) => self.name_globs.iter().any(|g| g.is_match(name)),
+            None => false,
+        }
+    }
+
+    fn build_glob(&self, pattern: &str) -> io::Result<GlobMatcher> {
+        GlobBuilder::new(pattern)
+            .case_insensitive(self.case_insensitive)
+            .literal_separator(true)
+            .build()
+            .map(|g| g.compile_matcher())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+
+    /// Makes the pattern absolute by expanding `~` and resolving it against the root,
+    /// which is expected to be escaped already when the pattern is a glob.
+    fn resolve(pattern: &str, root: &Path, is_glob: bool) -> PathBuf {
+        let home = pattern
+            .strip_prefix('~')
+            .filter(|rest| rest.is_empty() || rest.starts_with(['/', '\\']))
+            .zip(env::home_dir());
+
+        let path = match home {
+            Some((rest, home)) => {
+                let home = if is_glob {
+                    PathBuf::from(globset::escape(&home.to_string_lossy()))
+                } else {
+                    home
+                };
+                home.join(rest.trim_start_matches(['/', '\\']))
+            }
+            None => PathBuf::from(pattern),
+        };
+
+        let path = if path.is_absolute() {
+            path
+        } else {
+            root.join(path)
+        };
+
+        // remove `.` and `..` components without touching the filesystem,
+        // since globs and ignored paths don't have to exist
+        path.components()
+            .fold(PathBuf::new(), |mut acc, component| {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        acc.pop();
+                    }
+                    component => acc.push(component),
+                }
+                acc
+            })
+    }
+
+    fn normalize_case(&self, path: &Path) -> PathBuf {
+        if self.case_insensitive {
+            PathBuf::from(path.to_string_lossy().to_lowercase())
+        } else {
+            path.to_path_buf()
+        }
+    }
+
+    /// Checks whether the filesystem holding the path is case-insensitive by looking
+    /// up the path with the case of its name flipped.
+    #[cfg(unix)]
+    fn is_case_insensitive(path: &Path) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        path.ancestors()
+            .find_map(|p| {
+                let name = p.file_name()?.to_str()?;
+                let flipped = name
+                    .chars()
+                    .map(|c| {
+                        if c.is_uppercase() {
+                            c.to_lowercase().next().unwrap_or(c)
+                        } else {
+                            c.to_uppercase().next().unwrap_or(c)
+                        }
+                    })
+                    .collect::<String>();
+
+                if flipped == name {
+                    return None;
+                }
+
+                let original = fs::metadata(p).ok()?;
+
+                Some(match fs::metadata(p.with_file_name(flipped)) {
+                    Ok(flipped) => {
+                        flipped.dev() == original.dev() && flipped.ino() == original.ino()
+                    }
+                    Err(_) => false,
+                })
+            })
+            .unwrap_or(cfg!(target_os = "macos"))
+    }
+
+    #[cfg(not(unix))]
+    fn is_case_insensitive(_path: &Path) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use rstest::rstest;
+
+    use crate::ignore_matcher::IgnoreMatcher;
+
+    #[rstest]
+    // literal paths match on component boundaries
+    #[case("/work/foo", "/work/foo", true)]
+    #[case("/work/foo", "/work/foo/target", true)]
+    #[case("/work/foo/", "/work/foo/target", true)]
+    #[case("/work/foo", "/work/foobar", false)]
+    #[case("/work/foo", "/work", false)]
+    // relative paths are resolved against the root
+    #[case("foo/target", "/root/foo/target", true)]
+    #[case("./foo", "/root/foo/target", true)]
+    #[case("../other", "/other/target", true)]
+    #[case("foo/target", "/work/foo/target", false)]
+    // names match at any depth
+    #[case("legacy", "/root/legacy", true)]
+    #[case("legacy", "/root/a/b/legacy", true)]
+    #[case("legacy", "/root/a/legacy-2", false)]
+    // globs
+    #[case("*-legacy", "/root/a/app-legacy", true)]
+    #[case("*-legacy", "/root/a/app-legacy2", false)]
+    #[case("apps/*", "/root/apps/web", true)]
+    #[case("apps/*", "/root/apps/web/nested", false)]
+    #[case("apps/*", "/root/other/apps/web", false)]
+    #[case("**/vendor/*", "/root/a/b/vendor/lib", true)]
+    #[case("/work/{a,b}", "/work/b", true)]
+    #[case("/work/{a,b}", "/work/c", false)]
+    fn test_is_ignored(#[case] pattern: &str, #[case] path: &str, #[case] ignored: bool) {
+        let matcher = IgnoreMatcher::with_case_sensitivity(
+            &[PathBuf::from(pattern)],
+            Path::new("/root"),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(matcher.is_ignored(Path::new(path)), ignored);
+    }
+
+    #[rstest]
+    #[case("/Work/Foo", "/work/foo/target", false, false)]
+    #[case("/Work/Foo", "/work/foo/target", true, true)]
+    #[case("Legacy", "/root/legacy", false, false)]
+    #[case("Legacy", "/root/legacy", true, true)]
+    #[case("*.BAK", "/root/a.bak", true, true)]
+    fn test_is_ignored_case(
+        #[case] pattern: &str,
+        #[case] path: &str,
+        #[case] case_insensitive: bool,
+        #[case] ignored: bool,
+    ) {
+        let matcher = IgnoreMatcher::with_case_sensitivity(
+            &[PathBuf::from(pattern)],
+            Path::new("/root"),
+            case_insensitive,
+        )
+        .unwrap();
+
+        assert_eq!(matcher.is_ignored(Path::new(path)), ignored);
+    }
+
+    #[test]
+    fn test_is_ignored_home() {
+        let Some(home) = std::env::home_dir() else {
+            return;
+        };
+
+        let matcher = IgnoreMatcher::with_case_sensitivity(
+            &[PathBuf::from("~/foo")],
+            Path::new("/search"),
+            false,
+        )
+        .unwrap();
+
+        assert!(matcher.is_ignored(&home.join("foo").join("target")));
+        assert!(!matcher.is_ignored(Path::new("/search/foo")));
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        let result = IgnoreMatcher::with_case_sensitivity(
+            &[PathBuf::from("/work/[a")],
+            Path::new("/root"),
+            false,
+        );
+
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,30 +1,73 @@
-use std::io::stdout;
+use std::io::{self, IsTerminal, stdin, stdout};
+use std::process::ExitCode;
 
 use clap::Parser;
 
+pub mod cargo_config;
+pub mod cargo_lock;
 pub mod command;
+pub mod config;
 pub mod dir_helpers;
+pub mod disk_space;
+pub mod ignore_matcher;
+pub mod mounts;
+pub mod report;
+pub mod rust_artifacts;
+pub mod summary;
+pub mod toolchains;
+pub mod trash;
+pub mod tui;
 pub mod wipe;
 pub mod wipe_params;
 pub mod writer;
 
-use crate::command::Command;
-use crate::wipe::Wipe;
+use crate::command::{Command, ConfigCommand, WipeCommand};
+use crate::config::Config;
+use crate::wipe::{RunStatus, Wipe};
 use crate::wipe_params::WipeParams;
 
 #[cfg(test)]
 mod tests;
 
-fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<ExitCode> {
     let mut stdout = stdout();
     let command = Command::parse();
 
     match command {
         Command::Wipe(args) => {
-            let params = WipeParams::new(&args)?;
-            Wipe::new(&mut stdout, &params).run()?;
+            let config = Config::load()?;
+
+            if let Some(WipeCommand::Config(ConfigCommand::Show)) = args.command {
+                config.show(&mut stdout)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            let params = match WipeParams::new(&args, &config) {
+                Ok(params) => params,
+                Err(e) => return invalid_arguments(e),
+            };
+
+            let stdin = stdin();
+            let mut input = stdin.lock();
+            let mut wipe = Wipe::new(&mut stdout, &params);
+
+            // prompts are only answered from a terminal, so that piped input can't confirm them
+            if params.interactive && stdin.is_terminal() {
+                wipe = wipe.with_input(&mut input);
+            }
+
+            wipe.run().map(ExitCode::from).or_else(invalid_arguments)
         }
     }
+}
+
+/// Reports the arguments that can't be used like the ones rejected while parsing
+/// them, with exit code 2, and fails on the other errors.
+fn invalid_arguments(error: io::Error) -> anyhow::Result<ExitCode> {
+    if error.kind() != io::ErrorKind::InvalidInput {
+        return Err(error.into());
+    }
 
-    Ok(())
+    eprintln!("Error: {error}");
+    Ok(RunStatus::InvalidArguments.into())
 }
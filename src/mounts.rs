@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Filesystems that expose kernel state rather than files on disk.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "sysfs",
+    "tracefs",
+];
+
+/// The folders whose tmpfs mounts hold runtime state, such as `/run/user/1000`.
+const RUNTIME_DIRS: &[&str] = &["/dev", "/run", "/var/run"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub fs_type: String,
+}
+
+impl Mount {
+    /// Returns `true` for pseudo filesystems, and for tmpfs mounts in runtime folders.
+    pub fn is_pseudo(&self) -> bool {
+        PSEUDO_FILESYSTEMS.contains(&self.fs_type.as_str())
+            || (self.fs_type == "tmpfs"
+                && RUNTIME_DIRS.iter().any(|dir| self.path.starts_with(dir)))
+    }
+}
+
+/// Returns the mount points of the pseudo filesystems, which are never searched.
+///
+/// The mounts are read from `/proc/self/mountinfo`, so none are found on systems
+/// without it.
+pub fn pseudo_mounts() -> HashSet<PathBuf> {
+    fs::read_to_string("/proc/self/mountinfo")
+        .map(|content| {
+            parse_mountinfo(&content)
+                .into_iter()
+                .filter(Mount::is_pseudo)
+                .map(|m| m.path)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the contents of `/proc/self/mountinfo`, skipping malformed lines.
+///
+/// Every line looks like
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue`,
+/// where the fifth field is the mount point, and the field after the `-` separator
+/// is the filesystem type.
+pub fn parse_mountinfo(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let path = fields.nth(4)?;
+            let fs_type = fields.skip_while(|f| *f != "-").nth(1)?;
+
+            Some(Mount {
+                path: PathBuf::from(unescape(path)),
+                fs_type: unescape(fs_type),
+            })
+        })
+        .collect()
+}
+
+/// Decodes the octal escapes used for spaces, tabs, newlines, and backslashes.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 4)
+            .filter(|_| bytes[i] == b'\\')
+            .and_then(|octal| std::str::from_utf8(octal).ok())
+            .and_then(|octal| u8::from_str_radix(octal, 8).ok());
+
+        match escape {
+            Some(byte) => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            None => {
+                unescaped.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rstest::rstest;
+
+    use crate::mounts::{Mount, parse_mountinfo};
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let content = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+23 22 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+24 22 0:22 / /sys rw,nosuid shared:2 master:1 - sysfs sysfs rw
+25 22 0:45 / /home/user/my\\040share rw,relatime - fuse.sshfs host:/ rw
+malformed line";
+
+        let mounts = parse_mountinfo(content);
+
+        assert_eq!(
+            mounts,
+            vec![
+                Mount {
+                    path: PathBuf::from("/"),
+                    fs_type: String::from("ext4"),
+                },
+                Mount {
+                    path: PathBuf::from("/proc"),
+                    fs_type: String::from("proc"),
+                },
+                Mount {
+                    path: PathBuf::from("/sys"),
+                    fs_type: String::from("sysfs"),
+                },
+                Mount {
+                    path: PathBuf::from("/home/user/my share"),
+                    fs_type: String::from("fuse.sshfs"),
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("/proc", "proc", true)]
+    #[case("/sys/fs/cgroup", "cgroup2", true)]
+    #[case("/run/user/1000", "tmpfs", true)]
+    #[case("/dev/shm", "tmpfs", true)]
+    #[case("/tmp", "tmpfs", false)]
+    #[case("/home", "ext4", false)]
+    #[case("/mnt/share", "nfs", false)]
+    fn test_is_pseudo(#[case] path: &str, #[case] fs_type: &str, #[case] pseudo: bool) {
+        let mount = Mount {
+            path: PathBuf::from(path),
+            fs_type: String::from(fs_type),
+        };
+
+        assert_eq!(mount.is_pseudo(), pseudo);
+    }
+}
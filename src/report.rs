@@ -0,0 +1,257 @@
+use std::io;
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+
+use crate::command::LanguageEnum;
+use crate::dir_helpers::{DirInfo, PathStatus, PathToDelete, ScanError};
+use crate::disk_space::FsSpace;
+use crate::summary::Summary;
+use crate::wipe_params::WipeParams;
+
+/// The version of the JSON report, to be bumped on breaking changes to its structure.
+pub const REPORT_VERSION: u32 = 1;
+
+/// A machine-readable report of a run, written when `--format json` is used.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub version: u32,
+    pub dry_run: bool,
+    pub trash: bool,
+    /// The budget for the space that can be reclaimed with `--check`, compared to
+    /// the `disk_size` of the total `wipe`
+    pub max_reclaimable: Option<usize>,
+    pub languages: Vec<String>,
+    pub roots: Vec<String>,
+    pub candidates: Vec<ReportCandidate>,
+    pub summaries: Vec<ReportSummary>,
+    pub total: Option<ReportSummary>,
+    /// The free space of the filesystems holding the folders to wipe
+    pub filesystems: Vec<ReportFilesystem>,
+    /// The folders and files that could not be read, which are missing from the totals
+    pub scan_errors: Vec<ReportScanError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportCandidate {
+    pub path: String,
+    pub language: String,
+    pub directory: String,
+    pub file_count: Option<usize>,
+    pub size: Option<usize>,
+    /// The space allocated on disk, counting hard-linked files once
+    pub disk_size: Option<usize>,
+    /// Seconds since the Unix epoch
+    pub last_used: Option<u64>,
+    pub status: PathStatus,
+    pub wiped: bool,
+    /// Whether the folder was wiped by moving it to the trash
+    pub trashed: bool,
+    pub error: Option<String>,
+    /// The Cargo projects that build into the folder, when it is a custom target directory
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    /// The folders inside it that are wiped instead of the whole folder, with
+    /// `--rust-only` or `--rust-keep`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parts: Option<Vec<ReportPart>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportPart {
+    pub name: String,
+    pub paths: Vec<String>,
+    pub file_count: Option<usize>,
+    pub size: Option<usize>,
+    pub disk_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportScanError {
+    pub path: String,
+    pub error: String,
+}
+
+/// The space of a filesystem in bytes, as measured with `statvfs`.
+#[derive(Debug, Serialize)]
+pub struct ReportFilesystem {
+    pub mount_point: String,
+    pub used_before: u64,
+    pub free_before: u64,
+    pub used_after: Option<u64>,
+    pub free_after: Option<u64>,
+    /// Negative when something else took up more space while wiping
+    pub freed: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportTotals {
+    pub dir_count: usize,
+    pub file_count: usize,
+    pub size: usize,
+    pub disk_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportLanguageTotals {
+    pub language: String,
+    #[serde(flatten)]
+    pub totals: ReportTotals,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportSummary {
+    pub root: String,
+    /// The number of folders found, including the ones that were skipped
+    pub found: usize,
+    pub previous: Option<ReportTotals>,
+    pub wipe: ReportTotals,
+    pub languages: Vec<ReportLanguageTotals>,
+    pub ignored: ReportTotals,
+    /// The folders protected with a `.cargo-wipe-keep` file
+    pub protected: ReportTotals,
+    /// The folders reached through a symlink with `--follow-symlinks`
+    pub symlinked: ReportTotals,
+    pub recent: ReportTotals,
+    pub filtered: ReportTotals,
+    pub declined: ReportTotals,
+    pub trashed: ReportTotals,
+    pub after: Option<ReportTotals>,
+}
+
+impl Report {
+    pub fn new(params: &WipeParams) -> Self {
+        Self {
+            version: REPORT_VERSION,
+            dry_run: !params.wipe,
+            trash: params.trash,
+            max_reclaimable: params.max_reclaimable,
+            languages: params.languages.iter().map(|l| l.to_string()).collect(),
+            roots: params
+                .paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+            candidates: Vec::new(),
+            summaries: Vec::new(),
+            total: None,
+            filesystems: Vec::new(),
+            scan_errors: Vec::new(),
+        }
+    }
+
+    pub fn add_candidate(
+        &mut self,
+        path_to_delete: &PathToDelete,
+        status: PathStatus,
+        error: &Option<io::Error>,
+    ) {
+        let dir_info = path_to_delete.dir_info.as_ref().ok();
+        let wiped = !self.dry_run && status == PathStatus::Selected && error.is_none();
+
+        self.candidates.push(ReportCandidate {
+            path: path_to_delete.path.clone(),
+            language: LanguageEnum::from(&path_to_delete.directory).to_string(),
+            directory: path_to_delete.directory.to_string(),
+            file_count: dir_info.map(|i| i.file_count),
+            size: dir_info.map(|i| i.size),
+            disk_size: dir_info.map(|i| i.disk_size),
+            last_used: dir_info
+                .and_then(|i| i.last_used)
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            status,
+            wiped,
+            trashed: wiped && self.trash,
+            error: error.as_ref().map(|e| e.to_string()),
+            projects: path_to_delete
+                .projects
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+            parts: path_to_delete.parts.as_ref().map(|parts| {
+                parts
+                    .iter()
+                    .map(|part| {
+                        let dir_info = part.dir_info.as_ref().ok();
+
+                        ReportPart {
+                            name: part.name.clone(),
+                            paths: part.paths.iter().map(|p| p.display().to_string()).collect(),
+                            file_count: dir_info.map(|i| i.file_count),
+                            size: dir_info.map(|i| i.size),
+                            disk_size: dir_info.map(|i| i.disk_size),
+                        }
+                    })
+                    .collect()
+            }),
+        });
+    }
+}
+
+impl From<&ScanError> for ReportScanError {
+    fn from(scan_error: &ScanError) -> Self {
+        Self {
+            path: scan_error.path.display().to_string(),
+            error: scan_error.error.to_string(),
+        }
+    }
+}
+
+impl From<&FsSpace> for ReportFilesystem {
+    fn from(space: &FsSpace) -> Self {
+        Self {
+            mount_point: space.mount_point.display().to_string(),
+            used_before: space.before.used,
+            free_before: space.before.free,
+            used_after: space.after.map(|a| a.used),
+            free_after: space.after.map(|a| a.free),
+            freed: space.freed(),
+        }
+    }
+}
+
+impl From<&DirInfo> for ReportTotals {
+    fn from(dir_info: &DirInfo) -> Self {
+        Self {
+            dir_count: dir_info.dir_count,
+            file_count: dir_info.file_count,
+            size: dir_info.size,
+            disk_size: dir_info.disk_size,
+        }
+    }
+}
+
+impl From<&Summary> for ReportSummary {
+    fn from(summary: &Summary) -> Self {
+        let mut wipe = ReportTotals::from(&summary.wipe_info);
+        wipe.dir_count = summary
+            .language_infos
+            .iter()
+            .map(|(_, i)| i.dir_count)
+            .sum();
+
+        Self {
+            root: summary.title.clone(),
+            found: summary.wipe_info.dir_count,
+            previous: summary.previous_info.as_ref().map(ReportTotals::from),
+            wipe,
+            languages: summary
+                .language_infos
+                .iter()
+                .map(|(language, dir_info)| ReportLanguageTotals {
+                    language: language.to_string(),
+                    totals: dir_info.into(),
+                })
+                .collect(),
+            ignored: (&summary.ignore_info).into(),
+            protected: (&summary.protect_info).into(),
+            symlinked: (&summary.symlink_info).into(),
+            recent: (&summary.recent_info).into(),
+            filtered: (&summary.filter_info).into(),
+            declined: (&summary.decline_info).into(),
+            trashed: (&summary.trash_info).into(),
+            after: summary.after().as_ref().map(ReportTotals::from),
+        }
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cargo_lock::CargoLock;
+use crate::command::RustArtifactEnum;
+use crate::dir_helpers::{DirInfo, Hardlinks};
+use crate::toolchains;
+
+/// The parts of the `target` folders to wipe, instead of the whole folders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RustArtifacts {
+    /// Only wipe the artifacts of these kinds, set with `--rust-only`
+    Only(Vec<RustArtifactEnum>),
+    /// Wipe everything but the artifacts of these kinds, set with `--rust-keep`
+    Keep(Vec<RustArtifactEnum>),
+    /// Only wipe what was built by other compilers than these versions, which are the
+    /// installed toolchains, set with `--rust-toolchains uninstalled`
+    Uninstalled(Vec<String>),
+    /// Only wipe what was built by other compilers than the one that last built the
+    /// folder, set with `--rust-toolchains not-current`
+    NotCurrent,
+    /// Wipe the least recently used units until the folder takes up at most this much
+    /// space on disk, set with `--rust-max-size`
+    MaxSize(usize),
+    /// Only wipe the artifacts of the package versions that are no longer in the
+    /// `Cargo.lock` of the projects, set with `--rust-unlocked`
+    Unlocked,
+}
+
+impl RustArtifacts {
+    /// Lists the parts to wipe inside the `target` folder, each with a name and its
+    /// paths, which are never nested inside each other.
+    ///
+    /// With kinds, every part is a folder named after its path inside `target`. Only
+    /// folders are wiped, so when keeping some folders of a profile, the files next to
+    /// them are kept too. So are the files at the top of the `target` folder, which
+    /// mark it as a build folder.
+    ///
+    /// With toolchains, every part holds the artifacts built by one compiler, and is
+    /// named after its version. Nothing is wiped when the compilers can't be told.
+    ///
+    /// With a maximum size, every part holds the units of a profile to wipe, given the
+    /// space the `target` folder takes up on disk.
+    ///
+    /// With unlocked versions, every part holds the artifacts of a package version that
+    /// none of the projects building into the `target` folder has locked. Nothing is
+    /// wiped when they have no `Cargo.lock`.
+    pub fn parts(
+        &self,
+        target: &Path,
+        projects: &[PathBuf],
+        disk_size: usize,
+    ) -> Vec<(String, Vec<PathBuf>)> {
+        let folders = match self {
+            RustArtifacts::Only(kinds) => RustArtifacts::find(target, kinds),
+            RustArtifacts::Keep(kinds) => {
+                RustArtifacts::complement(target, &RustArtifacts::find(target, kinds))
+            }
+            RustArtifacts::Uninstalled(installed) => {
+                return RustArtifacts::built_by(target, |version| {
+                    !installed.iter().any(|i| i == version)
+                });
+            }
+            RustArtifacts::NotCurrent => {
+                let Some(current) = toolchains::current(target) else {
+                    return Vec::new();
+                };
+
+                return RustArtifacts::built_by(target, |version| version != current);
+            }
+            RustArtifacts::MaxSize(max_size) => {
+                return RustArtifacts::least_recently_used(target, disk_size, *max_size);
+            }
+            RustArtifacts::Unlocked => {
+                let Some(lock) = CargoLock::read(projects) else {
+                    return Vec::new();
+                };
+
+                return RustArtifacts::unlocked(target, &lock);
+            }
+        };
+
+        folders
+            .into_iter()
+            .map(|path| {
+                let name = path.strip_prefix(target).unwrap_or(&path).display();
+                (name.to_string(), vec![path])
+            })
+            .collect()
+    }
+
+    /// Finds the folders of the given kinds, for the host and for every target triple
+    /// that was built for.
+    fn find(target: &Path, kinds: &[RustArtifactEnum]) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+
+        for base in &bases(target) {
+            let profiles = profiles(base);
+
+            for kind in kinds {
+                match kind {
+                    RustArtifactEnum::Debug => found.push(base.join("debug")),
+                    RustArtifactEnum::Release => found.push(base.join("release")),
+                    RustArtifactEnum::Doc => found.push(base.join("doc")),
+                    RustArtifactEnum::Package => found.push(base.join("package")),
+                    RustArtifactEnum::Incremental => {
+                        found.extend(profiles.iter().map(|p| p.join("incremental")));
+                    }
+                    RustArtifactEnum::BuildScripts => {
+                        found.extend(profiles.iter().map(|p| p.join("build")));
+                    }
+                }
+            }
+        }
+
+        found.retain(|path| is_dir(path));
+        found.sort();
+
+        // drop the folders inside another one, e.g. `debug/incremental` inside `debug`
+        let mut parts: Vec<PathBuf> = Vec::with_capacity(found.len());
+        for path in found {
+            if !parts.last().is_some_and(|last| path.starts_with(last)) {
+                parts.push(path);
+            }
+        }
+
+        parts
+    }
+
+    /// Groups the artifacts built by the compilers whose version matches, by version,
+    /// in every profile of the host and of the target triples.
+    fn built_by(target: &Path, matches: impl Fn(&str) -> bool) -> Vec<(String, Vec<PathBuf>)> {
+        let mut parts = BTreeMap::<String, Vec<PathBuf>>::new();
+
+        for profile in bases(target).iter().flat_map(|base| profiles(base)) {
+            for (version, mut paths) in toolchains::artifacts_by_version(&profile) {
+                if matches(&version) {
+                    parts
+                        .entry(format!("rustc {version}"))
+                        .or_default()
+                        .append(&mut paths);
+                }
+            }
+        }
+
+        parts.into_iter().collect()
+    }
+
+    /// Picks the least recently used units of all the profiles, until what is left of
+    /// the `target` folder fits in the maximum size, grouped by profile. The units that
+    /// are used the most stay, so most builds remain incremental. The units are ranked
+    /// by the times of their files, since searching moves the access time of folders.
+    ///
+    /// The incremental caches of the crates are picked the same way, each on its own,
+    /// since they are named after the crate rather than the unit.
+    fn least_recently_used(
+        target: &Path,
+        disk_size: usize,
+        max_size: usize,
+    ) -> Vec<(String, Vec<PathBuf>)> {
+        if disk_size <= max_size {
+            return Vec::new();
+        }
+
+        let mut sized = Vec::new();
+
+        // a file linked in several units only counts in one, like in the `target` folder
+        let hardlinks = Hardlinks::default();
+
+        for profile in bases(target).iter().flat_map(|base| profiles(base)) {
+            let caches = entries(&profile.join("incremental"))
+                .into_iter()
+                .map(|path| vec![path]);
+
+            for paths in units(&profile).into_values().chain(caches) {
+                let mut dir_info = DirInfo::new(0, 0, 0);
+                for path in &paths {
+                    if let Ok(path_info) = DirInfo::dir_size_with(path, &hardlinks) {
+                        dir_info += path_info;
+                    }
+                }
+
+                sized.push((profile.clone(), paths, dir_info));
+            }
+        }
+
+        // the units that were never used come first, as `None` sorts first
+        sized.sort_by_key(|(_, _, dir_info)| dir_info.last_used);
+
+        let mut disk_size = disk_size;
+        let mut parts = BTreeMap::<PathBuf, (usize, Vec<PathBuf>)>::new();
+
+        for (profile, mut paths, dir_info) in sized {
+            if disk_size <= max_size {
+                break;
+            }
+
+            disk_size = disk_size.saturating_sub(dir_info.disk_size);
+
+            let (count, part) = parts.entry(profile).or_default();
+            *count += 1;
+            part.append(&mut paths);
+        }
+
+        parts
+            .into_iter()
+            .map(|(profile, (count, paths))| {
+                let profile = profile.strip_prefix(target).unwrap_or(&profile).display();
+                let crates = if count == 1 { "crate" } else { "crates" };
+
+                (
+                    format!("{profile} ({count} least recently used {crates})"),
+                    paths,
+                )
+            })
+            .collect()
+    }
+
+    /// Groups the units of the package versions that are not locked, by package and
+    /// version, in every profile of the host and of the target triples.
+    ///
+    /// Units whose version can't be told are kept, which are the ones of packages that
+    /// are not from a registry and the outputs of the build scripts, and so are the
+    /// incremental caches.
+    fn unlocked(target: &Path, lock: &CargoLock) -> Vec<(String, Vec<PathBuf>)> {
+        let mut parts = BTreeMap::<String, Vec<PathBuf>>::new();
+
+        for profile in bases(target).iter().flat_map(|base| profiles(base)) {
+            for mut paths in units(&profile).into_values() {
+                let Some((name, version)) = package_version(&paths) else {
+                    continue;
+                };
+
+                if !lock.is_locked(&name, &version) {
+                    parts
+                        .entry(format!("{name} {version}"))
+                        .or_default()
+                        .append(&mut paths);
+                }
+            }
+        }
+
+        parts.into_iter().collect()
+    }
+
+    /// Lists the folders inside `target` that neither are nor hold a kept folder,
+    /// looking inside the ones that hold a kept folder.
+    fn complement(dir: &Path, kept: &[PathBuf]) -> Vec<PathBuf> {
+        let mut parts = Vec::new();
+
+        for path in subdirs(dir) {
+            if kept.contains(&path) {
+                continue;
+            }
+
+            if kept.iter().any(|k| k.starts_with(&path)) {
+                parts.append(&mut RustArtifacts::complement(&path, kept));
+            } else {
+                parts.push(path);
+            }
+        }
+
+        parts
+    }
+}
+
+/// Groups the artifacts in the profile folder (e.g. `target/debug`) by the unit they
+/// were built for, which is a crate built with some settings, by the hash of the unit.
+/// The artifacts of a unit have its hash in their name, in `.fingerprint`, `deps` and
+/// `build`.
+pub fn units(profile: &Path) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut units = BTreeMap::<String, Vec<PathBuf>>::new();
+
+    for dir in [".fingerprint", "deps", "build"] {
+        for path in entries(&profile.join(dir)) {
+            if let Some(unit) = unit_hash(&path) {
+                units.entry(unit.to_string()).or_default().push(path);
+            }
+        }
+    }
+
+    units
+}
+
+/// Finds the package and version a unit was built for, from its fingerprint folder
+/// (e.g. `.fingerprint/serde-0123456789abcdef`) and its dep-info file, which is in
+/// `deps` for crates, or in the folder of the build script in `build`.
+fn package_version(paths: &[PathBuf]) -> Option<(String, String)> {
+    let fingerprint = paths.iter().find(|path| {
+        path.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == ".fingerprint")
+    })?;
+    let (name, _) = fingerprint.file_name()?.to_str()?.rsplit_once('-')?;
+
+    let is_dep_info = |path: &PathBuf| path.extension().is_some_and(|e| e == "d");
+    let dep_info = paths
+        .iter()
+        .find(|path| is_dep_info(path))
+        .cloned()
+        .or_else(|| {
+            paths
+                .iter()
+                .flat_map(|path| entries(path))
+                .find(|path| is_dep_info(path))
+        })?;
+    let dep_info = fs::read_to_string(dep_info).ok()?;
+
+    let version = CargoLock::dep_info_version(name, &dep_info)?;
+
+    Some((name.to_string(), version))
+}
+
+/// Extracts the hash of the unit from the name of one of its artifacts, which ends with
+/// it, before any extension (e.g. `libfoo-0123456789abcdef.rlib`).
+fn unit_hash(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.split('.').next()?;
+    let (_, hash) = stem.rsplit_once('-')?;
+
+    (hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit())).then_some(hash)
+}
+
+/// Lists everything directly inside the folder, sorted, leaving out anything that
+/// can't be read.
+pub fn entries(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+
+    paths.sort();
+    paths
+}
+
+/// Lists the folders holding the same layout as the `target` folder, which are the
+/// folder itself and the ones of the target triples.
+fn bases(target: &Path) -> Vec<PathBuf> {
+    let mut bases = vec![target.to_path_buf()];
+
+    bases.extend(
+        subdirs(target)
+            .into_iter()
+            .filter(|dir| !is_profile(dir) && subdirs(dir).iter().any(|d| is_profile(d))),
+    );
+
+    bases
+}
+
+fn profiles(base: &Path) -> Vec<PathBuf> {
+    subdirs(base)
+        .into_iter()
+        .filter(|dir| is_profile(dir))
+        .collect()
+}
+
+/// Lists the folders directly inside the folder, sorted, leaving out symlinks and
+/// anything that can't be read.
+fn subdirs(path: &Path) -> Vec<PathBuf> {
+    entries(path)
+        .into_iter()
+        .filter(|path| is_dir(path))
+        .collect()
+}
+
+fn is_dir(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|data| data.is_dir())
+}
+
+/// Whether the folder holds the output of a profile, like `debug` or `release`.
+fn is_profile(path: &Path) -> bool {
+    is_dir(&path.join(".fingerprint")) || is_dir(&path.join("deps"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use rstest::rstest;
+
+    use crate::command::RustArtifactEnum;
+    use crate::rust_artifacts::{self, RustArtifacts};
+    use crate::tests::helpers::test_run::TestRun;
+
+    const LAYOUT: [&str; 12] = [
+        "debug/deps",
+        "debug/build",
+        "debug/incremental",
+        "release/.fingerprint",
+        "release/build",
+        "doc/build",
+        "package",
+        "tmp",
+        "x86_64-unknown-linux-musl/release/deps",
+        "x86_64-unknown-linux-musl/release/incremental",
+        "x86_64-unknown-linux-musl/doc",
+        "bench/deps",
+    ];
+
+    #[rstest]
+    #[case::debug(
+        RustArtifacts::Only(vec![RustArtifactEnum::Debug, RustArtifactEnum::Incremental]),
+        vec!["bench/incremental", "debug", "x86_64-unknown-linux-musl/release/incremental"]
+    )]
+    #[case::build_scripts(
+        RustArtifacts::Only(vec![RustArtifactEnum::BuildScripts, RustArtifactEnum::Doc]),
+        vec!["debug/build", "doc", "release/build", "x86_64-unknown-linux-musl/doc"]
+    )]
+    #[case::package(RustArtifacts::Only(vec![RustArtifactEnum::Package]), vec!["package"])]
+    #[case::keep_release(
+        RustArtifacts::Keep(vec![RustArtifactEnum::Release]),
+        vec!["bench", "debug", "doc", "package", "tmp", "x86_64-unknown-linux-musl/doc"]
+    )]
+    #[case::keep_build_scripts(
+        RustArtifacts::Keep(vec![RustArtifactEnum::BuildScripts, RustArtifactEnum::Doc]),
+        vec![
+            "bench",
+            "debug/deps",
+            "debug/incremental",
+            "package",
+            "release/.fingerprint",
+            "tmp",
+            "x86_64-unknown-linux-musl/release",
+        ]
+    )]
+    fn test_parts(#[case] rust_artifacts: RustArtifacts, #[case] expected: Vec<&str>) {
+        let test_run = TestRun::empty();
+        let target = test_run.path.join("target");
+
+        for dir in LAYOUT {
+            std::fs::create_dir_all(target.join(dir)).unwrap();
+        }
+        // `bench` is a custom profile
+        std::fs::create_dir_all(target.join("bench/incremental")).unwrap();
+        std::fs::File::create(target.join(".rustc_info.json")).unwrap();
+
+        let parts = rust_artifacts.parts(&target, &[], 0);
+        let names = parts.iter().map(|(name, _)| name).collect::<Vec<_>>();
+
+        assert_eq!(names, expected, "{rust_artifacts:?}");
+
+        for (name, paths) in &parts {
+            assert_eq!(paths, &vec![target.join(name)]);
+        }
+    }
+
+    #[rstest]
+    #[case("libfoo-0123456789abcdef.rlib", Some("0123456789abcdef"))]
+    #[case("foo_bar-0123456789abcdef", Some("0123456789abcdef"))]
+    #[case("foo-bar-0123456789abcdef.long-type-1.txt", Some("0123456789abcdef"))]
+    #[case("foo", None)]
+    #[case("foo-bar", None)]
+    fn test_unit_hash(#[case] name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(rust_artifacts::unit_hash(Path::new(name)), expected);
+    }
+
+    #[test]
+    fn test_parts_missing() {
+        let target = PathBuf::from("/nonexistent/target");
+
+        assert!(
+            RustArtifacts::Only(vec![RustArtifactEnum::Debug])
+                .parts(&target, &[], 0)
+                .is_empty()
+        );
+        assert!(
+            RustArtifacts::Keep(vec![RustArtifactEnum::Debug])
+                .parts(&target, &[], 0)
+                .is_empty()
+        );
+    }
+}
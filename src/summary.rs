@@ -0,0 +1,229 @@
+use crate::command::{DirectoryEnum, LanguageEnum};
+use crate::dir_helpers::DirInfo;
+
+/// The totals of a run, either for a single root path or combined for all of them.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub title: String,
+    pub previous_info: Option<DirInfo>,
+    pub wipe_info: DirInfo,
+    pub ignore_info: DirInfo,
+    pub protect_info: DirInfo,
+    /// The folders reached through a symlink, which are never wiped
+    pub symlink_info: DirInfo,
+    pub recent_info: DirInfo,
+    pub filter_info: DirInfo,
+    pub decline_info: DirInfo,
+    /// The wiped folders that were moved to the trash, and still take up space
+    pub trash_info: DirInfo,
+    pub language_infos: Vec<(LanguageEnum, DirInfo)>,
+}
+
+impl Summary {
+    pub fn new(title: impl Into<String>, languages: &[LanguageEnum]) -> Self {
+        Self {
+            title: title.into(),
+            previous_info: None,
+            wipe_info: DirInfo::new(0, 0, 0),
+            ignore_info: DirInfo::new(0, 0, 0),
+            protect_info: DirInfo::new(0, 0, 0),
+            symlink_info: DirInfo::new(0, 0, 0),
+            recent_info: DirInfo::new(0, 0, 0),
+            filter_info: DirInfo::new(0, 0, 0),
+            decline_info: DirInfo::new(0, 0, 0),
+            trash_info: DirInfo::new(0, 0, 0),
+            language_infos: languages
+                .iter()
+                .map(|l| (l.clone(), DirInfo::new(0, 0, 0)))
+                .collect(),
+        }
+    }
+
+    pub fn add_wiped(&mut self, directory: DirectoryEnum, dir_info: &DirInfo) {
+        // the number of folders found is set separately
+        self.wipe_info.file_count += dir_info.file_count;
+        self.wipe_info.size += dir_info.size;
+        self.wipe_info.disk_size += dir_info.disk_size;
+
+        let language_info = self
+            .language_infos
+            .iter_mut()
+            .find(|(l, _)| l.directories() == [directory]);
+
+        if let Some((_, language_info)) = language_info {
+            Summary::add(language_info, dir_info);
+        }
+    }
+
+    pub fn add_ignored(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.ignore_info, dir_info);
+    }
+
+    pub fn add_protected(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.protect_info, dir_info);
+    }
+
+    pub fn add_symlinked(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.symlink_info, dir_info);
+    }
+
+    pub fn add_recent(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.recent_info, dir_info);
+    }
+
+    pub fn add_filtered(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.filter_info, dir_info);
+    }
+
+    pub fn add_declined(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.decline_info, dir_info);
+    }
+
+    pub fn add_trashed(&mut self, dir_info: &DirInfo) {
+        Summary::add(&mut self.trash_info, dir_info);
+    }
+
+    /// Adds a single folder to the totals.
+    fn add(info: &mut DirInfo, dir_info: &DirInfo) {
+        info.dir_count += 1;
+        info.file_count += dir_info.file_count;
+        info.size += dir_info.size;
+        info.disk_size += dir_info.disk_size;
+    }
+
+    /// The totals that are left after wiping, if the previous totals are known.
+    pub fn after(&self) -> Option<DirInfo> {
+        self.previous_info.map(|previous_info| {
+            let mut after = DirInfo::new(
+                previous_info
+                    .dir_count
+                    .saturating_sub(self.wipe_info.dir_count),
+                previous_info
+                    .file_count
+                    .saturating_sub(self.wipe_info.file_count),
+                previous_info.size.saturating_sub(self.wipe_info.size),
+            );
+            after.disk_size = previous_info
+                .disk_size
+                .saturating_sub(self.wipe_info.disk_size);
+            after
+        })
+    }
+
+    /// Combines the totals of several summaries into a new one.
+    pub fn combine(title: impl Into<String>, summaries: &[Summary]) -> Self {
+        let languages = summaries
+            .first()
+            .map(|s| {
+                s.language_infos
+                    .iter()
+                    .map(|(l, _)| l.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut combined = Summary::new(title, &languages);
+
+        for summary in summaries {
+            combined.wipe_info += summary.wipe_info;
+            combined.ignore_info += summary.ignore_info;
+            combined.protect_info += summary.protect_info;
+            combined.symlink_info += summary.symlink_info;
+            combined.recent_info += summary.recent_info;
+            combined.filter_info += summary.filter_info;
+            combined.decline_info += summary.decline_info;
+            combined.trash_info += summary.trash_info;
+
+            if let Some(previous_info) = summary.previous_info {
+                let combined_previous_info =
+                    combined.previous_info.get_or_insert(DirInfo::new(0, 0, 0));
+                *combined_previous_info += previous_info;
+            }
+
+            for ((_, combined_info), (_, info)) in combined
+                .language_infos
+                .iter_mut()
+                .zip(summary.language_infos.iter())
+            {
+                *combined_info += *info;
+            }
+        }
+
+        combined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::{DirectoryEnum, LanguageEnum};
+    use crate::dir_helpers::DirInfo;
+    use crate::summary::Summary;
+
+    #[test]
+    fn test_combine() {
+        let languages = vec![LanguageEnum::Node, LanguageEnum::Rust];
+
+        let mut first = Summary::new("first", &languages);
+        first.previous_info = Some(DirInfo::new(10, 100, 1000));
+        first.wipe_info.dir_count = 1;
+        first.add_wiped(DirectoryEnum::Target, &DirInfo::new(1, 10, 100));
+        first.add_ignored(&DirInfo::new(1, 5, 50));
+        first.add_protected(&DirInfo::new(1, 6, 60));
+        first.add_recent(&DirInfo::new(1, 7, 70));
+
+        let mut second = Summary::new("second", &languages);
+        second.previous_info = Some(DirInfo::new(20, 200, 2000));
+        second.wipe_info.dir_count = 2;
+        second.add_wiped(DirectoryEnum::NodeModules, &DirInfo::new(1, 20, 200));
+        second.add_wiped(DirectoryEnum::Target, &DirInfo::new(1, 30, 300));
+        second.add_filtered(&DirInfo::new(1, 8, 80));
+
+        let combined = Summary::combine("Total", &[first, second]);
+
+        assert_eq!(combined.title, "Total");
+
+        let previous_info = combined.previous_info.unwrap();
+        assert_eq!(previous_info.file_count, 300);
+        assert_eq!(previous_info.size, 3000);
+
+        assert_eq!(combined.wipe_info.dir_count, 3);
+        assert_eq!(combined.wipe_info.file_count, 60);
+        assert_eq!(combined.wipe_info.size, 600);
+
+        assert_eq!(combined.ignore_info.dir_count, 1);
+        assert_eq!(combined.ignore_info.size, 50);
+
+        assert_eq!(combined.protect_info.dir_count, 1);
+        assert_eq!(combined.protect_info.size, 60);
+
+        assert_eq!(combined.recent_info.dir_count, 1);
+        assert_eq!(combined.recent_info.size, 70);
+
+        assert_eq!(combined.filter_info.dir_count, 1);
+        assert_eq!(combined.filter_info.size, 80);
+
+        let (language, node_info) = &combined.language_infos[0];
+        assert_eq!(language, &LanguageEnum::Node);
+        assert_eq!(node_info.dir_count, 1);
+        assert_eq!(node_info.size, 200);
+
+        let (language, rust_info) = &combined.language_infos[1];
+        assert_eq!(language, &LanguageEnum::Rust);
+        assert_eq!(rust_info.dir_count, 2);
+        assert_eq!(rust_info.size, 400);
+    }
+
+    #[test]
+    fn test_after_does_not_underflow() {
+        let mut summary = Summary::new("first", &[LanguageEnum::Rust]);
+        summary.previous_info = Some(DirInfo::new(1, 10, 100));
+        summary.wipe_info.dir_count = 2;
+        summary.add_wiped(DirectoryEnum::Target, &DirInfo::new(1, 20, 200));
+
+        let after = summary.after().unwrap();
+        assert_eq!(after.dir_count, 0);
+        assert_eq!(after.file_count, 0);
+        assert_eq!(after.size, 0);
+        assert_eq!(after.disk_size, 0);
+    }
+}
@@ -1,6 +1,8 @@
 use rand::{Rng, prelude::ThreadRng, rng};
 use rand_distr::Alphanumeric;
+use std::fs::{File, FileTimes};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use crate::command::{DirectoryEnum, LanguageEnum};
 
@@ -27,6 +29,33 @@ impl From<&TestRun> for PathBuf {
 
 impl TestRun {
     pub fn new(language: &LanguageEnum, hits_count: u32, ignores_count: u32) -> Self {
+        let mut run = TestRun::empty();
+
+        run.generate_hits(language, hits_count);
+        run.generate_ignores(language, ignores_count);
+        run.generate_no_hits();
+        run.generate_different_language_hits(language);
+        run.generate_invalid(language);
+        run.generate_partial(language);
+
+        run
+    }
+
+    pub fn new_multi(languages: &[LanguageEnum], hits_count: u32) -> Self {
+        let mut run = TestRun::empty();
+
+        for language in languages {
+            run.generate_hits(language, hits_count);
+            run.generate_invalid(language);
+            run.generate_partial(language);
+        }
+        run.generate_no_hits();
+
+        run
+    }
+
+    /// Creates an empty folder with a unique name, which is removed when dropped.
+    pub fn empty() -> Self {
         let mut rng = rng();
         let name = TestRun::generate_folder_name(&mut rng);
 
@@ -36,26 +65,17 @@ impl TestRun {
 
         std::fs::create_dir_all(&path).unwrap();
 
-        let mut run = TestRun {
+        TestRun {
             rng,
             path,
             hits: Vec::new(),
             ignores: Vec::new(),
             misses: Vec::new(),
-        };
-
-        run.generate_hits(language, hits_count);
-        run.generate_ignores(language, ignores_count);
-        run.generate_no_hits();
-        run.generate_different_language_hits(language);
-        run.generate_invalid(language);
-        run.generate_partial(language);
-
-        run
+        }
     }
 
     fn generate_hits(&mut self, language: &LanguageEnum, hits_count: u32) {
-        let directory: DirectoryEnum = language.into();
+        let directory = language.directories()[0];
 
         for _ in 0..hits_count {
             let name = TestRun::generate_folder_name(&mut self.rng);
@@ -76,7 +96,7 @@ impl TestRun {
     }
 
     fn generate_ignores(&mut self, language: &LanguageEnum, ignores_count: u32) {
-        let directory: DirectoryEnum = language.into();
+        let directory = language.directories()[0];
 
         for _ in 0..ignores_count {
             let name = TestRun::generate_folder_name(&mut self.rng);
@@ -126,7 +146,7 @@ impl TestRun {
     }
 
     fn generate_invalid(&mut self, language: &LanguageEnum) {
-        let directory: DirectoryEnum = language.into();
+        let directory = language.directories()[0];
 
         if language == &LanguageEnum::Rust {
             let name = TestRun::generate_folder_name(&mut self.rng);
@@ -142,7 +162,7 @@ impl TestRun {
     }
 
     fn generate_partial(&mut self, language: &LanguageEnum) {
-        let directory: DirectoryEnum = language.into();
+        let directory = language.directories()[0];
 
         let name = TestRun::generate_folder_name(&mut self.rng);
         let name_inner = TestRun::generate_folder_name(&mut self.rng);
@@ -158,6 +178,35 @@ impl TestRun {
         self.misses.push(path);
     }
 
+    /// Adds a file of the given size to the folder.
+    pub fn fill(path: &Path, size: usize) {
+        std::fs::write(path.join("data"), vec![0; size]).unwrap();
+    }
+
+    /// Adds a file to the folder and makes all its files look as if they were
+    /// last modified and accessed `age` ago.
+    pub fn set_age(path: &Path, age: Duration) {
+        File::create(path.join("file")).unwrap();
+
+        for entry in std::fs::read_dir(path).unwrap() {
+            TestRun::set_times(&entry.unwrap().path(), age);
+        }
+    }
+
+    /// Makes the file or folder itself look as if it was last modified and accessed
+    /// `age` ago.
+    pub fn set_times(path: &Path, age: Duration) {
+        let time = SystemTime::now() - age;
+        let times = FileTimes::new().set_accessed(time).set_modified(time);
+
+        let file = if path.is_dir() {
+            File::open(path)
+        } else {
+            File::options().write(true).open(path)
+        };
+        file.unwrap().set_times(times).unwrap();
+    }
+
     fn generate_folder_name(rng: &mut impl Rng) -> String {
         rng.sample_iter(&Alphanumeric)
             .take(16)
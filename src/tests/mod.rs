@@ -1,3 +1,4 @@
-mod helpers;
+pub mod helpers;
+mod search;
 mod wipe;
 mod wipe_permissions;
@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::command::{DirectoryEnum, LanguageEnum};
+use crate::dir_helpers::{DirInfo, Hardlinks, SearchOptions};
+use crate::ignore_matcher::IgnoreMatcher;
+use crate::tests::helpers::test_run::TestRun;
+
+#[rstest]
+#[case(LanguageEnum::Node)]
+#[case(LanguageEnum::Rust)]
+#[case(LanguageEnum::Terraform)]
+fn search_matches_dir_size(#[case] language: LanguageEnum) {
+    let test_run = TestRun::new(&language, 3, 3);
+    let legacy = test_run.path.join("legacy").join("src");
+    std::fs::create_dir_all(&legacy).unwrap();
+    TestRun::fill(&legacy, 4096);
+
+    let mut ignores = test_run.ignores.clone();
+    ignores.push(PathBuf::from("legacy"));
+
+    let matcher = IgnoreMatcher::new(&ignores, &test_run.path).unwrap();
+    let directory = language.directories()[0];
+
+    let options = SearchOptions {
+        directories: &[directory],
+        ignores: &matcher,
+        follow_symlinks: false,
+        one_file_system: false,
+        skipped_mounts: &HashSet::new(),
+        min_depth: None,
+        max_depth: None,
+        cargo_config: None,
+    };
+
+    let result = DirInfo::search(&test_run.path, &options);
+    let expected = DirInfo::dir_size(&test_run.path).unwrap();
+
+    // the root totals include the ignored folders, which are sized but not searched
+    assert_eq!(result.total.dir_count, expected.dir_count);
+    assert_eq!(result.total.file_count, expected.file_count);
+    assert_eq!(result.total.size, expected.size);
+
+    assert_eq!(
+        result.paths.len(),
+        test_run.hits.len() + test_run.ignores.len()
+    );
+
+    for path_to_delete in result.paths {
+        let dir_info = path_to_delete.dir_info.unwrap();
+        let expected = DirInfo::dir_size(&path_to_delete.path).unwrap();
+
+        assert_eq!(dir_info.file_count, expected.file_count);
+        assert_eq!(dir_info.size, expected.size);
+    }
+}
+
+#[cfg(unix)]
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn search_with_symlinks(#[case] follow_symlinks: bool) {
+    use std::os::unix::fs::symlink;
+
+    let test_run = TestRun::new(&LanguageEnum::Rust, 2, 0);
+    let outside = TestRun::new(&LanguageEnum::Rust, 1, 0);
+
+    // a loop back to the root, a symlinked `target`, and a symlink to another project
+    let linked = test_run.path.join("linked-project");
+    std::fs::create_dir_all(&linked).unwrap();
+    symlink(&test_run.path, linked.join("loop")).unwrap();
+    symlink(&outside.hits[0], linked.join("target")).unwrap();
+    symlink(&outside.path, test_run.path.join("outside")).unwrap();
+
+    // a symlink to a project inside the root should not report it twice
+    let inside = test_run.hits[0].parent().unwrap();
+    symlink(inside, test_run.path.join("inside")).unwrap();
+
+    let matcher = IgnoreMatcher::new(&[], &test_run.path).unwrap();
+    let options = SearchOptions {
+        directories: &[DirectoryEnum::Target],
+        ignores: &matcher,
+        follow_symlinks,
+        one_file_system: false,
+        skipped_mounts: &HashSet::new(),
+        min_depth: None,
+        max_depth: None,
+        cargo_config: None,
+    };
+
+    let result = DirInfo::search(&test_run.path, &options);
+
+    let mut paths = result
+        .paths
+        .into_iter()
+        .map(|p| (PathBuf::from(p.path), p.symlinked))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut expected = test_run
+        .hits
+        .iter()
+        .map(|p| (p.clone(), false))
+        .collect::<Vec<_>>();
+    if follow_symlinks {
+        let relative = outside.hits[0].strip_prefix(&outside.path).unwrap();
+        expected.push((test_run.path.join("outside").join(relative), true));
+    }
+    expected.sort();
+
+    assert_eq!(paths, expected);
+}
+
+#[test]
+fn search_skips_mounts() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 3, 0);
+
+    let skipped = test_run.hits[0].parent().unwrap().to_path_buf();
+    let skipped_mounts = HashSet::from([skipped.clone()]);
+
+    let matcher = IgnoreMatcher::new(&[], &test_run.path).unwrap();
+    let options = SearchOptions {
+        directories: &[DirectoryEnum::Target],
+        ignores: &matcher,
+        follow_symlinks: false,
+        one_file_system: true,
+        skipped_mounts: &skipped_mounts,
+        min_depth: None,
+        max_depth: None,
+        cargo_config: None,
+    };
+
+    let result = DirInfo::search(&test_run.path, &options);
+
+    // the skipped mount point is neither searched nor counted
+    let paths = result
+        .paths
+        .into_iter()
+        .map(|p| PathBuf::from(p.path))
+        .collect::<Vec<_>>();
+
+    assert_eq!(paths.len(), 2);
+    assert!(paths.iter().all(|p| !p.starts_with(&skipped)));
+
+    let expected = DirInfo::dir_size(&test_run.path).unwrap();
+    let skipped = DirInfo::dir_size(&skipped).unwrap();
+
+    assert_eq!(
+        result.total.file_count,
+        expected.file_count - skipped.file_count
+    );
+    assert_eq!(
+        result.total.dir_count,
+        expected.dir_count - skipped.dir_count - 1
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn dir_size_counts_disk_usage() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let target = &test_run.hits[0];
+
+    // a file with two more hard links, and a sparse file
+    let data = target.join("data");
+    std::fs::write(&data, vec![1; 64 * 1024]).unwrap();
+    std::fs::hard_link(&data, target.join("link-1")).unwrap();
+    std::fs::hard_link(&data, target.join("link-2")).unwrap();
+
+    let sparse = std::fs::File::create(target.join("sparse")).unwrap();
+    sparse.set_len(64 * 1024 * 1024).unwrap();
+
+    let dir_info = DirInfo::dir_size(target).unwrap();
+
+    assert_eq!(dir_info.file_count, 5);
+    assert_eq!(dir_info.size, 3 * 64 * 1024 + 64 * 1024 * 1024);
+
+    // the hard-linked file is only counted once, and the sparse file takes no space
+    assert!(dir_info.disk_size >= 64 * 1024);
+    assert!(dir_info.disk_size < 2 * 64 * 1024);
+}
+
+#[cfg(unix)]
+#[test]
+fn dir_size_skips_files_linked_outside() {
+    let test_run = TestRun::new(&LanguageEnum::Node, 1, 0);
+    let hit = &test_run.hits[0];
+
+    // a file from a store outside the folder, like the ones pnpm links to
+    let store = test_run.path.join("store");
+    std::fs::create_dir_all(&store).unwrap();
+    std::fs::write(store.join("data"), vec![1; 64 * 1024]).unwrap();
+    std::fs::hard_link(store.join("data"), hit.join("data")).unwrap();
+
+    let dir_info = DirInfo::dir_size(hit).unwrap();
+
+    // wiping the folder frees nothing, since the store keeps the file
+    assert_eq!(dir_info.file_count, 1);
+    assert_eq!(dir_info.size, 64 * 1024);
+    assert_eq!(dir_info.disk_size, 0);
+
+    // the matches found by the search are sized the same way
+    let matcher = IgnoreMatcher::new(&[], &test_run.path).unwrap();
+    let options = SearchOptions {
+        directories: &[DirectoryEnum::NodeModules],
+        ignores: &matcher,
+        follow_symlinks: false,
+        one_file_system: false,
+        skipped_mounts: &HashSet::new(),
+        min_depth: None,
+        max_depth: None,
+        cargo_config: None,
+    };
+
+    let result = DirInfo::search(&test_run.path, &options);
+
+    assert_eq!(result.paths[0].dir_info.as_ref().unwrap().disk_size, 0);
+
+    // while the totals count the file once, since all its links are in the path
+    let expected = DirInfo::dir_size(&test_run.path).unwrap();
+    assert!(expected.disk_size >= 64 * 1024);
+    assert_eq!(result.total.disk_size, expected.disk_size);
+
+    // and so do the folders sized together
+    let hardlinks = Hardlinks::default();
+    let store_info = DirInfo::dir_size_with(&store, &hardlinks).unwrap();
+    let hit_info = DirInfo::dir_size_with(hit, &hardlinks).unwrap();
+    let blocks =
+        std::os::unix::fs::MetadataExt::blocks(&std::fs::metadata(hit.join("data")).unwrap());
+    assert_eq!(
+        store_info.disk_size + hit_info.disk_size,
+        blocks as usize * 512
+    );
+}
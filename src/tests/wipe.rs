@@ -1,12 +1,16 @@
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{io::Cursor, println};
 
 use rstest::rstest;
 use yansi::Paint as _;
 
-use crate::command::{DirectoryEnum, LanguageEnum};
+use crate::command::{FormatEnum, LanguageEnum, RustArtifactEnum};
+use crate::dir_helpers::{DirInfo, KEEP_MARKER};
+use crate::report::REPORT_VERSION;
+use crate::rust_artifacts::RustArtifacts;
 use crate::tests::helpers::test_run::TestRun;
-use crate::wipe::Wipe;
+use crate::wipe::{RunStatus, Wipe};
 use crate::wipe_params::WipeParams;
 use crate::writer::{SPACING_FILES, SPACING_SIZE};
 
@@ -19,13 +23,14 @@ use crate::writer::{SPACING_FILES, SPACING_SIZE};
 #[case(LanguageEnum::Terraform, true)]
 fn run_with_hits(#[case] language: LanguageEnum, #[case] wipe: bool) {
     let test_run = TestRun::new(&language, 3, 0);
-    let directory: DirectoryEnum = (&language).into();
+    let directory = language.directories()[0];
 
     let params = WipeParams {
         wipe,
-        path: PathBuf::from(&test_run),
-        language: language.clone(),
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language.clone()],
         ignores: Vec::new(),
+        ..WipeParams::default()
     };
 
     let mut buff = Cursor::new(Vec::new());
@@ -84,7 +89,7 @@ fn run_with_hits(#[case] language: LanguageEnum, #[case] wipe: bool) {
     } else {
         let expected = format!(
             "Run {} to wipe all folders found. {}",
-            format!("cargo wipe {} -w", params.language).red(),
+            format!("cargo wipe {} -w", params.languages_formatted()).red(),
             "USE WITH CAUTION!".red()
         );
         assert!(output.contains(&expected));
@@ -103,9 +108,10 @@ fn run_no_hits(#[case] language: LanguageEnum, #[case] wipe: bool) {
 
     let params = WipeParams {
         wipe,
-        path: PathBuf::from(&test_run),
-        language,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language],
         ignores: Vec::new(),
+        ..WipeParams::default()
     };
 
     let mut buff = Cursor::new(Vec::new());
@@ -157,9 +163,10 @@ fn run_with_ignores(#[case] language: LanguageEnum, #[case] wipe: bool) {
 
     let params = WipeParams {
         wipe,
-        path: PathBuf::from(&test_run),
-        language,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language],
         ignores: test_run.ignores.clone(),
+        ..WipeParams::default()
     };
 
     let mut buff = Cursor::new(Vec::new());
@@ -212,3 +219,1094 @@ fn run_with_ignores(#[case] language: LanguageEnum, #[case] wipe: bool) {
     let expected = format!("{}", "Ignored".yellow());
     assert!(output.contains(&expected));
 }
+
+#[rstest]
+#[case(vec![LanguageEnum::Node, LanguageEnum::Rust], false)]
+#[case(vec![LanguageEnum::Node, LanguageEnum::Rust], true)]
+#[case(vec![LanguageEnum::Node, LanguageEnum::Rust, LanguageEnum::Terraform], false)]
+#[case(vec![LanguageEnum::Node, LanguageEnum::Rust, LanguageEnum::Terraform], true)]
+fn run_with_multiple_languages(#[case] languages: Vec<LanguageEnum>, #[case] wipe: bool) {
+    let test_run = TestRun::new_multi(&languages, 2);
+
+    let params = WipeParams {
+        wipe,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: languages.clone(),
+        ignores: Vec::new(),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // header
+    for language in &languages {
+        let directory = language.directories()[0];
+        let expected = format!(r#""{}""#, directory.cyan());
+        assert!(output.contains(&expected));
+    }
+
+    // body
+    // hits of every language should be listed and wiped if wipe is true
+    assert_eq!(test_run.hits.len(), languages.len() * 2);
+    for path in &test_run.hits {
+        let expected = String::from(path.to_str().unwrap());
+        assert_eq!(output.matches(&expected).count(), 1);
+        assert_eq!(path.exists(), !wipe);
+    }
+
+    // misses should not be listed and not wiped
+    for path in &test_run.misses {
+        let expected = String::from(path.to_str().unwrap());
+        assert!(!output.contains(&expected));
+        assert!(path.exists());
+    }
+
+    // summary should contain a subtotal for every language
+    let label = if wipe { "Wiped" } else { "Can wipe" };
+    for language in &languages {
+        let expected = format!("{}", format!("{label} ({language})").red());
+        assert!(output.contains(&expected));
+    }
+
+    // footer
+    if !wipe {
+        let expected = format!("cargo wipe {} -w", params.languages_formatted());
+        assert!(output.contains(&expected));
+    }
+}
+
+#[rstest]
+#[case(LanguageEnum::Node, false)]
+#[case(LanguageEnum::Node, true)]
+#[case(LanguageEnum::Rust, false)]
+#[case(LanguageEnum::Rust, true)]
+fn run_with_multiple_paths(#[case] language: LanguageEnum, #[case] wipe: bool) {
+    let first_run = TestRun::new(&language, 2, 0);
+    let second_run = TestRun::new(&language, 3, 0);
+    let empty_run = TestRun::new(&language, 0, 0);
+
+    let params = WipeParams {
+        wipe,
+        paths: vec![
+            PathBuf::from(&first_run),
+            PathBuf::from(&second_run),
+            PathBuf::from(&empty_run),
+        ],
+        languages: vec![language],
+        ignores: Vec::new(),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // header
+    let expected = format!("{}", params.paths_formatted().cyan());
+    assert!(output.contains(&expected));
+
+    // body
+    for path in first_run.hits.iter().chain(second_run.hits.iter()) {
+        let expected = String::from(path.to_str().unwrap());
+        assert!(output.contains(&expected));
+        assert_eq!(path.exists(), !wipe);
+    }
+
+    // summary should be displayed for every path with hits and for the total
+    for run in [&first_run, &second_run] {
+        let expected = format!("{}", run.path.display().cyan());
+        assert!(output.contains(&expected));
+    }
+
+    let expected = format!("{}", empty_run.path.display().cyan());
+    assert!(!output.contains(&expected));
+
+    let expected = format!("{}", "Total".cyan());
+    assert!(output.contains(&expected));
+}
+
+#[rstest]
+#[case(LanguageEnum::Node)]
+#[case(LanguageEnum::Rust)]
+#[case(LanguageEnum::Terraform)]
+fn run_with_ignored_parents(#[case] language: LanguageEnum) {
+    let test_run = TestRun::new(&language, 3, 0);
+
+    let first_parent = test_run.hits[0].parent().unwrap();
+    let first_parent_name = first_parent.file_name().unwrap();
+
+    let second_parent = test_run.hits[1].parent().unwrap();
+    let second_parent_prefix = second_parent.display().to_string();
+    let second_parent_prefix = &second_parent_prefix[..second_parent_prefix.len() - 1];
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language],
+        ignores: vec![
+            // relative to the searched path
+            PathBuf::from(".").join(first_parent_name),
+            // a prefix of the name must not ignore the folder
+            PathBuf::from(second_parent_prefix),
+        ],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // hits inside ignored folders are not searched for, listed, or wiped
+    let expected = String::from(test_run.hits[0].to_str().unwrap());
+    assert!(!output.contains(&expected));
+    assert!(test_run.hits[0].exists());
+
+    // the remaining hits are wiped
+    for path in &test_run.hits[1..] {
+        let expected = String::from(path.to_str().unwrap());
+        assert!(output.contains(&expected));
+        assert!(!path.exists());
+    }
+}
+
+#[rstest]
+#[case(LanguageEnum::Node, false)]
+#[case(LanguageEnum::Node, true)]
+#[case(LanguageEnum::Rust, false)]
+#[case(LanguageEnum::Rust, true)]
+#[case(LanguageEnum::Terraform, false)]
+#[case(LanguageEnum::Terraform, true)]
+fn run_with_older_than(#[case] language: LanguageEnum, #[case] wipe: bool) {
+    let test_run = TestRun::new(&language, 3, 0);
+    let day = Duration::from_secs(24 * 60 * 60);
+
+    TestRun::set_age(&test_run.hits[0], day * 2);
+    TestRun::set_age(&test_run.hits[1], day * 40);
+    TestRun::set_age(&test_run.hits[2], day * 400);
+
+    let params = WipeParams {
+        wipe,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language],
+        older_than: Some(day * 30),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    let lines = output.lines();
+    println!("{output}");
+
+    // header
+    assert!(output.contains("Last used"));
+
+    // recently used hits should be listed and skipped
+    let expected = String::from(test_run.hits[0].to_str().unwrap());
+    let line = lines.clone().find(|l| l.contains(&expected)).unwrap();
+    assert!(line.contains("2 days ago"));
+    assert!(line.contains("[Recent]"));
+    assert!(test_run.hits[0].exists());
+
+    // old hits should be listed and wiped if wipe is true
+    for (path, last_used) in test_run.hits[1..]
+        .iter()
+        .zip(["40 days ago", "400 days ago"])
+    {
+        let expected = String::from(path.to_str().unwrap());
+        let line = lines.clone().find(|l| l.contains(&expected)).unwrap();
+        assert!(line.contains(last_used));
+        assert!(!line.contains("[Recent]"));
+        assert_eq!(path.exists(), !wipe);
+    }
+
+    // summary should show the skipped hits
+    let expected = format!("{}", "Skipped (recent)".yellow());
+    assert!(output.contains(&expected));
+}
+
+#[test]
+fn run_with_older_than_twice() {
+    let test_run = TestRun::new(&LanguageEnum::Node, 1, 0);
+    let day = Duration::from_secs(24 * 60 * 60);
+
+    // the folders inside the hit look as old as its files
+    TestRun::set_age(&test_run.hits[0], day * 40);
+    let package = test_run.hits[0].join("package");
+    std::fs::create_dir_all(&package).unwrap();
+    TestRun::set_age(&package, day * 40);
+    TestRun::set_times(&package, day * 40);
+
+    let params = WipeParams {
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Node],
+        older_than: Some(day * 30),
+        ..WipeParams::default()
+    };
+
+    // searching reads the folders, which must not make them look recently used
+    for _ in 0..2 {
+        let mut buff = Cursor::new(Vec::new());
+        Wipe::new(&mut buff, &params).run().unwrap();
+
+        let output = std::str::from_utf8(buff.get_ref()).unwrap();
+        println!("{output}");
+
+        let expected = String::from(test_run.hits[0].to_str().unwrap());
+        let line = output.lines().find(|l| l.contains(&expected)).unwrap();
+        assert!(line.contains("40 days ago"));
+        assert!(!line.contains("[Recent]"));
+    }
+}
+
+#[rstest]
+#[case(None, None, vec![true, true, true])]
+#[case(Some(5 * 1024), None, vec![false, true, true])]
+#[case(None, Some(1), vec![false, false, true])]
+#[case(Some(5 * 1024), Some(5), vec![false, true, true])]
+#[case(Some(50 * 1024), Some(2), vec![false, false, true])]
+#[case(Some(500 * 1024), None, vec![false, false, false])]
+fn run_with_size_filters(
+    #[case] min_size: Option<usize>,
+    #[case] top: Option<usize>,
+    #[case] wiped: Vec<bool>,
+) {
+    let test_run = TestRun::new(&LanguageEnum::Node, 3, 0);
+
+    for (path, size) in test_run.hits.iter().zip([1024, 10 * 1024, 100 * 1024]) {
+        TestRun::fill(path, size);
+    }
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Node],
+        min_size,
+        top,
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // only the hits that pass the filters should be listed and wiped
+    for (path, wiped) in test_run.hits.iter().zip(&wiped) {
+        let expected = String::from(path.to_str().unwrap());
+        assert_eq!(output.contains(&expected), *wiped);
+        assert_eq!(path.exists(), !wiped);
+    }
+
+    // summary should show the filtered out hits
+    let filtered = wiped.iter().filter(|w| !**w).count();
+    let expected = match filtered {
+        0 => String::from("Filtered out"),
+        1 => format!("{}", "Filtered out (1 folder)".yellow()),
+        n => format!("{}", format!("Filtered out ({n} folders)").yellow()),
+    };
+    assert_eq!(output.contains(&expected), filtered > 0);
+}
+
+#[rstest]
+#[case(LanguageEnum::Node, false)]
+#[case(LanguageEnum::Node, true)]
+#[case(LanguageEnum::Rust, false)]
+#[case(LanguageEnum::Rust, true)]
+#[case(LanguageEnum::Terraform, false)]
+#[case(LanguageEnum::Terraform, true)]
+fn run_with_json_format(#[case] language: LanguageEnum, #[case] wipe: bool) {
+    let test_run = TestRun::new(&language, 3, 1);
+    let directory = language.directories()[0];
+
+    for path in &test_run.hits {
+        TestRun::fill(path, 1024);
+    }
+
+    let params = WipeParams {
+        wipe,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language.clone()],
+        ignores: test_run.ignores.clone(),
+        format: FormatEnum::Json,
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    let report: serde_json::Value = serde_json::from_str(output).unwrap();
+
+    assert_eq!(report["version"], REPORT_VERSION);
+    assert_eq!(report["dry_run"], !wipe);
+    assert_eq!(
+        report["languages"],
+        serde_json::json!([language.to_string()])
+    );
+    assert_eq!(
+        report["roots"],
+        serde_json::json!([test_run.path.display().to_string()])
+    );
+
+    // every hit and ignore should be reported
+    let candidates = report["candidates"].as_array().unwrap();
+    assert_eq!(candidates.len(), 4);
+
+    for path in &test_run.hits {
+        let candidate = candidates
+            .iter()
+            .find(|c| c["path"] == path.display().to_string())
+            .unwrap();
+
+        assert_eq!(candidate["language"], language.to_string());
+        assert_eq!(candidate["directory"], directory.to_string());
+        assert_eq!(candidate["status"], "selected");
+        assert_eq!(candidate["wiped"], wipe);
+        assert_eq!(candidate["trashed"], false);
+        assert!(candidate["error"].is_null());
+        assert!(candidate["size"].as_u64().unwrap() >= 1024);
+        assert!(candidate["last_used"].is_u64());
+    }
+
+    for path in &test_run.ignores {
+        let candidate = candidates
+            .iter()
+            .find(|c| c["path"] == path.display().to_string())
+            .unwrap();
+
+        assert_eq!(candidate["status"], "ignored");
+        assert_eq!(candidate["wiped"], false);
+    }
+
+    // totals
+    let total = &report["total"];
+    assert_eq!(total["found"], 4);
+    assert_eq!(total["wipe"]["dir_count"], 3);
+    assert!(total["wipe"]["size"].as_u64().unwrap() >= 3 * 1024);
+    assert_eq!(total["ignored"]["dir_count"], 1);
+    assert!(total["previous"]["size"].as_u64().unwrap() >= 3 * 1024);
+    assert_eq!(
+        total["after"]["size"].as_u64().unwrap(),
+        total["previous"]["size"].as_u64().unwrap() - total["wipe"]["size"].as_u64().unwrap()
+    );
+    assert_eq!(report["summaries"].as_array().unwrap().len(), 1);
+
+    // the filesystem holding the hits is measured again only after wiping
+    if cfg!(unix) {
+        let filesystems = report["filesystems"].as_array().unwrap();
+        assert_eq!(filesystems.len(), 1);
+        assert!(filesystems[0]["free_before"].is_u64());
+        assert_eq!(filesystems[0]["free_after"].is_u64(), wipe);
+        assert_eq!(filesystems[0]["freed"].is_i64(), wipe);
+    }
+
+    // no text should be written
+    assert!(!output.contains("Files #"));
+}
+
+#[rstest]
+#[case(LanguageEnum::Node)]
+#[case(LanguageEnum::Rust)]
+#[case(LanguageEnum::Terraform)]
+fn run_with_trash_dry_run(#[case] language: LanguageEnum) {
+    let test_run = TestRun::new(&language, 3, 0);
+
+    let params = WipeParams {
+        trash: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![language.clone()],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // nothing should be moved, and the footer should suggest wiping to the trash
+    for path in &test_run.hits {
+        assert!(path.exists());
+    }
+
+    assert!(!output.contains("Moved to trash"));
+    assert!(output.contains(&format!("cargo wipe {language} -w --trash")));
+}
+
+#[rstest]
+#[case("y\nn\ny\n", vec![true, false, true])]
+#[case("maybe\ny\nno\nyes\n", vec![true, false, true])]
+#[case("n\na\n", vec![false, true, true])]
+#[case("y\nq\n", vec![true, false, false])]
+#[case("y\n", vec![true, false, false])]
+fn run_with_interactive(#[case] answers: &str, #[case] wiped: Vec<bool>) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 3, 0);
+
+    let params = WipeParams {
+        wipe: true,
+        interactive: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    let mut input = Cursor::new(answers.as_bytes());
+    Wipe::new(&mut buff, &params)
+        .with_input(&mut input)
+        .run()
+        .unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // the folders are prompted for in the order they are listed
+    let mut hits = test_run.hits.clone();
+    hits.sort();
+
+    for (path, wiped) in hits.iter().zip(wiped.iter()) {
+        let expected = String::from(path.to_str().unwrap());
+        let line = output
+            .lines()
+            .find(|l| l.ends_with(&expected) || l.contains(&format!("{expected} ")))
+            .unwrap();
+
+        assert_eq!(path.exists(), !wiped);
+        assert_eq!(line.contains("[Declined]"), !wiped);
+    }
+
+    // only the confirmed folders are counted
+    let declined = wiped.iter().filter(|w| !**w).count();
+    assert_eq!(output.contains("Declined"), declined > 0);
+    assert_eq!(output.contains("All clear!"), declined < 3);
+}
+
+#[test]
+fn run_with_interactive_without_terminal() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+
+    let params = WipeParams {
+        wipe: true,
+        interactive: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    let error = Wipe::new(&mut buff, &params).run().unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(test_run.hits[0].exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn run_with_follow_symlinks() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let outside = TestRun::new(&LanguageEnum::Rust, 1, 0);
+
+    std::os::unix::fs::symlink(&outside.path, test_run.path.join("outside")).unwrap();
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        follow_symlinks: true,
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    let status = Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // the folder reached through the symlink is listed, but skipped rather than failing to wipe
+    let relative = outside.hits[0].strip_prefix(&outside.path).unwrap();
+    let linked = test_run.path.join("outside").join(relative);
+    let line = output
+        .lines()
+        .find(|l| l.contains(linked.to_str().unwrap()))
+        .unwrap();
+
+    assert!(line.contains("[Symlinked]"));
+    assert!(output.contains("Skipped (symlinked)"));
+    assert_eq!(status, RunStatus::Clean);
+    assert!(outside.hits[0].exists());
+    assert!(!test_run.hits[0].exists());
+}
+
+#[rstest]
+#[case(None, None, vec![true, true, true])]
+#[case(Some(2), None, vec![false, true, true])]
+#[case(None, Some(2), vec![true, true, false])]
+#[case(Some(2), Some(2), vec![false, true, false])]
+fn run_with_depth(
+    #[case] min_depth: Option<usize>,
+    #[case] max_depth: Option<usize>,
+    #[case] found: Vec<bool>,
+) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+
+    // folders at depths 1, 2 and 4
+    let shallow = test_run.path.join("target");
+    let deep = test_run.path.join("a").join("b").join("c").join("target");
+
+    for path in [&shallow, &deep] {
+        std::fs::create_dir_all(path).unwrap();
+        std::fs::File::create(path.join(".rustc_info.json")).unwrap();
+    }
+
+    let params = WipeParams {
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        min_depth,
+        max_depth,
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for (path, found) in [&shallow, &test_run.hits[0], &deep].iter().zip(found) {
+        let expected = format!("    {}", path.display());
+        assert_eq!(output.lines().any(|l| l.ends_with(&expected)), found);
+    }
+
+    // the header states the depth, and the totals are unknown when it is limited
+    let header = output.lines().next().unwrap();
+    if let Some(depth) = params.depth_formatted() {
+        assert!(header.contains(&depth));
+    }
+    assert_eq!(output.contains("Currently"), max_depth.is_none());
+    assert_eq!(output.contains("After wipe"), max_depth.is_none());
+}
+
+#[rstest]
+#[case(0, false, RunStatus::Clean)]
+#[case(0, true, RunStatus::Clean)]
+#[case(3, false, RunStatus::Found)]
+#[case(3, true, RunStatus::Clean)]
+fn run_with_status(#[case] hits: u32, #[case] wipe: bool, #[case] expected: RunStatus) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, hits, 0);
+
+    let params = WipeParams {
+        wipe,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    let status = Wipe::new(&mut buff, &params).run().unwrap();
+
+    assert_eq!(status, expected);
+}
+
+#[rstest]
+#[case(1024usize.pow(3), RunStatus::Clean)]
+#[case(1, RunStatus::OverBudget)]
+fn run_with_check(#[case] max_reclaimable: usize, #[case] expected: RunStatus) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 3, 0);
+
+    for path in &test_run.hits {
+        TestRun::fill(path, 1024);
+    }
+
+    let params = WipeParams {
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        max_reclaimable: Some(max_reclaimable),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    let status = Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    assert_eq!(status, expected);
+    assert!(output.contains("[CHECK]"));
+    assert_eq!(
+        output.contains("Over budget!"),
+        expected == RunStatus::OverBudget
+    );
+    assert!(!output.contains("to wipe all folders found"));
+
+    // nothing is wiped when checking
+    for path in &test_run.hits {
+        assert!(path.exists());
+    }
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn run_with_keep_marker(#[case] in_project: bool) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 3, 0);
+
+    // the marker is either in the project folder, or in the build folder itself
+    let protected = &test_run.hits[0];
+    let marker_dir = if in_project {
+        protected.parent().unwrap()
+    } else {
+        protected.as_path()
+    };
+    std::fs::File::create(marker_dir.join(KEEP_MARKER)).unwrap();
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for path in &test_run.hits {
+        let is_protected = path == protected;
+        let line = output
+            .lines()
+            .find(|l| l.contains(path.to_str().unwrap()))
+            .unwrap();
+
+        assert_eq!(line.contains("[Protected]"), is_protected);
+        assert_eq!(path.exists(), is_protected);
+    }
+
+    // the protected folder is tallied separately
+    let expected = format!("{}", "Protected".yellow());
+    assert!(output.contains(&expected));
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn run_with_shared_target_dir(#[case] outside: bool) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 0, 0);
+
+    // the target directory is either next to the projects, or outside the searched path
+    let root = test_run.path.join("projects");
+    let target_dir = if outside {
+        test_run.path.join("shared-build")
+    } else {
+        root.join("shared-build")
+    };
+    std::fs::create_dir_all(&target_dir).unwrap();
+    std::fs::File::create(target_dir.join(".rustc_info.json")).unwrap();
+
+    let projects = ["first", "second"].map(|name| root.join(name));
+    for project in &projects {
+        std::fs::create_dir_all(project.join(".cargo")).unwrap();
+        std::fs::File::create(project.join("Cargo.toml")).unwrap();
+        std::fs::write(
+            project.join(".cargo").join("config.toml"),
+            format!("[build]\ntarget-dir = \"{}\"", target_dir.display()),
+        )
+        .unwrap();
+    }
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![root],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // the shared folder is listed once, with every project that builds into it
+    let lines = output
+        .lines()
+        .filter(|l| l.contains(target_dir.to_str().unwrap()))
+        .collect::<Vec<_>>();
+    let expected = format!(
+        "[Used by {}, {}]",
+        projects[0].display(),
+        projects[1].display()
+    );
+
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].contains(&expected));
+    assert!(!target_dir.exists());
+
+    for project in &projects {
+        assert!(project.join("Cargo.toml").exists());
+    }
+}
+
+#[rstest]
+#[case(
+    RustArtifacts::Only(vec![RustArtifactEnum::Incremental, RustArtifactEnum::Doc]),
+    vec!["debug/incremental", "doc"],
+    vec!["debug/deps", "release/deps"]
+)]
+#[case(
+    RustArtifacts::Keep(vec![RustArtifactEnum::Release]),
+    vec!["debug", "doc"],
+    vec!["release/deps"]
+)]
+fn run_with_rust_artifacts(
+    #[case] rust_artifacts: RustArtifacts,
+    #[case] wiped: Vec<&str>,
+    #[case] kept: Vec<&str>,
+) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 2, 0);
+
+    for target in &test_run.hits {
+        for dir in ["debug/deps", "debug/incremental", "release/deps", "doc"] {
+            std::fs::create_dir_all(target.join(dir)).unwrap();
+            TestRun::fill(&target.join(dir), 1024);
+        }
+    }
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        rust_artifacts: Some(rust_artifacts),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for target in &test_run.hits {
+        assert!(target.join(".rustc_info.json").exists());
+
+        for dir in &wiped {
+            assert!(!target.join(dir).exists(), "{dir} should be wiped");
+        }
+        for dir in &kept {
+            assert!(target.join(dir).exists(), "{dir} should be kept");
+        }
+    }
+
+    // every part is listed below its folder, relative to it
+    for dir in &wiped {
+        let line = format!("  {dir}");
+        assert_eq!(
+            output.lines().filter(|l| l.ends_with(&line)).count(),
+            test_run.hits.len()
+        );
+    }
+}
+
+#[rstest]
+#[case(RustArtifacts::NotCurrent, true)]
+#[case(RustArtifacts::Uninstalled(vec![String::from(NEW_RUSTC)]), true)]
+#[case(RustArtifacts::Uninstalled(vec![String::from(OLD_RUSTC), String::from(NEW_RUSTC)]), false)]
+fn run_with_rust_toolchains(#[case] rust_artifacts: RustArtifacts, #[case] old_wiped: bool) {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let target = &test_run.hits[0];
+    let profile = target.join("debug");
+
+    std::fs::write(
+        target.join(".rustc_info.json"),
+        format!(
+            r#"{{"rustc_fingerprint":1,"outputs":{{"2":{{"success":true,"status":"","code":0,"stdout":"rustc {NEW_RUSTC}\nrelease: 1.95.0\n","stderr":""}}}},"successes":{{}}}}"#
+        ),
+    )
+    .unwrap();
+
+    // every unit has a fingerprint with the hash of its compiler, and a library with its version
+    let units = [
+        ("old", "1111111111111111", 1, OLD_RUSTC),
+        ("new", "2222222222222222", 2, NEW_RUSTC),
+    ];
+    let mut artifacts = Vec::new();
+
+    for (name, hash, compiler, version) in units {
+        let fingerprint = profile.join(".fingerprint").join(format!("{name}-{hash}"));
+        let deps = profile.join("deps");
+        std::fs::create_dir_all(&fingerprint).unwrap();
+        std::fs::create_dir_all(&deps).unwrap();
+
+        std::fs::write(
+            fingerprint.join(format!("lib-{name}.json")),
+            format!("{{\"rustc\":{compiler}}}"),
+        )
+        .unwrap();
+
+        let rmeta = deps.join(format!("lib{name}-{hash}.rmeta"));
+        std::fs::write(&rmeta, format!("rust\0\0\0\x09\x24rustc {version}\0")).unwrap();
+        TestRun::fill(&fingerprint, 1024);
+
+        artifacts.push([fingerprint, rmeta]);
+    }
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        rust_artifacts: Some(rust_artifacts),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for path in &artifacts[0] {
+        assert_eq!(path.exists(), !old_wiped, "{}", path.display());
+    }
+    for path in &artifacts[1] {
+        assert!(path.exists(), "{}", path.display());
+    }
+
+    // the artifacts are listed by the compiler that built them
+    let line = format!("  rustc {OLD_RUSTC}");
+    assert_eq!(output.lines().any(|l| l.ends_with(&line)), old_wiped);
+    assert!(target.join(".rustc_info.json").exists());
+}
+
+const OLD_RUSTC: &str = "1.90.0 (1159e78c4 2025-09-14)";
+const NEW_RUSTC: &str = "1.95.0 (59807616e 2026-04-14)";
+
+#[test]
+fn run_with_rust_max_size() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let target = &test_run.hits[0];
+    let profile = target.join("debug");
+
+    // every unit has a fingerprint and a library, which were last used that many days ago
+    let units = [
+        ("old", "1111111111111111", 30),
+        ("older", "2222222222222222", 60),
+        ("new", "3333333333333333", 1),
+    ];
+    let mut artifacts = Vec::new();
+
+    for (name, hash, days) in units {
+        let fingerprint = profile.join(".fingerprint").join(format!("{name}-{hash}"));
+        let deps = profile.join("deps");
+        std::fs::create_dir_all(&fingerprint).unwrap();
+        std::fs::create_dir_all(&deps).unwrap();
+
+        let rlib = deps.join(format!("lib{name}-{hash}.rlib"));
+        std::fs::write(&rlib, vec![0; 64 * 1024]).unwrap();
+        TestRun::set_age(&fingerprint, Duration::from_secs(days * 24 * 60 * 60));
+        TestRun::set_times(&rlib, Duration::from_secs(days * 24 * 60 * 60));
+
+        artifacts.push([fingerprint, rlib]);
+    }
+
+    // only the two least recently used units have to be wiped to fit in the budget
+    let disk_size = |path: &PathBuf| DirInfo::dir_size(path).unwrap().disk_size;
+    let unit_size = artifacts[2].iter().map(disk_size).sum::<usize>();
+    let max_size = disk_size(target) - unit_size - unit_size / 2;
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        rust_artifacts: Some(RustArtifacts::MaxSize(max_size)),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for (i, paths) in artifacts.iter().enumerate() {
+        for path in paths {
+            assert_eq!(path.exists(), i == 2, "{}", path.display());
+        }
+    }
+
+    assert!(
+        output
+            .lines()
+            .any(|l| l.ends_with("  debug (2 least recently used crates)"))
+    );
+    assert!(disk_size(target) <= max_size);
+}
+
+#[test]
+fn run_with_rust_unlocked() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let target = &test_run.hits[0];
+    let profile = target.join("debug");
+    let registry = "/home/u/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f";
+
+    std::fs::write(
+        target.parent().unwrap().join("Cargo.lock"),
+        "version = 4\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.228\"\n\n[[package]]\nname = \"app\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    // every unit has a fingerprint, and a dep-info file listing its sources
+    let units = [
+        (
+            "serde",
+            "1111111111111111",
+            format!("{registry}/serde-1.0.228/src/lib.rs"),
+        ),
+        (
+            "serde",
+            "2222222222222222",
+            format!("{registry}/serde-1.0.219/src/lib.rs"),
+        ),
+        (
+            "app",
+            "3333333333333333",
+            String::from("/work/app/src/main.rs"),
+        ),
+    ];
+    let mut artifacts = Vec::new();
+
+    for (name, hash, source) in units {
+        let fingerprint = profile.join(".fingerprint").join(format!("{name}-{hash}"));
+        let deps = profile.join("deps");
+        std::fs::create_dir_all(&fingerprint).unwrap();
+        std::fs::create_dir_all(&deps).unwrap();
+
+        let dep_info = deps.join(format!("{name}-{hash}.d"));
+        std::fs::write(&dep_info, format!("{}: {source}\n", dep_info.display())).unwrap();
+        TestRun::fill(&fingerprint, 1024);
+
+        artifacts.push([fingerprint, dep_info]);
+    }
+
+    // the build script of the stale version has its dep-info in its folder
+    let build_script = profile.join("build").join("serde-4444444444444444");
+    std::fs::create_dir_all(&build_script).unwrap();
+    std::fs::write(
+        build_script.join("build_script_build-4444444444444444.d"),
+        format!("build_script_build: {registry}/serde-1.0.219/build.rs\n"),
+    )
+    .unwrap();
+    let build_fingerprint = profile.join(".fingerprint").join("serde-4444444444444444");
+    std::fs::create_dir_all(&build_fingerprint).unwrap();
+
+    let params = WipeParams {
+        wipe: true,
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        rust_artifacts: Some(RustArtifacts::Unlocked),
+        ..WipeParams::default()
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    for (i, paths) in artifacts.iter().enumerate() {
+        for path in paths {
+            assert_eq!(path.exists(), i != 1, "{}", path.display());
+        }
+    }
+    assert!(!build_script.exists());
+    assert!(!build_fingerprint.exists());
+
+    // the stale versions are listed by crate
+    assert_eq!(
+        output
+            .lines()
+            .filter(|l| l.ends_with("  serde 1.0.219"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn run_with_rust_max_size_after_search() {
+    let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+    let target = &test_run.hits[0];
+    let profile = target.join("debug");
+    let day = Duration::from_secs(24 * 60 * 60);
+
+    // the recently used unit comes first when the folders are read, and every unit has
+    // a build script with an `out` folder, as old as its files
+    let units = [
+        ("new", "0000000000000000", 1),
+        ("old", "1111111111111111", 400),
+    ];
+    let mut fingerprints = Vec::new();
+
+    for (name, hash, days) in units {
+        let fingerprint = profile.join(".fingerprint").join(format!("{name}-{hash}"));
+        let out = profile
+            .join("build")
+            .join(format!("{name}-{hash}"))
+            .join("out");
+        std::fs::create_dir_all(&fingerprint).unwrap();
+        std::fs::create_dir_all(&out).unwrap();
+        TestRun::fill(&fingerprint, 64 * 1024);
+        TestRun::set_age(&fingerprint, day * days);
+        TestRun::set_age(&out, day * days);
+        TestRun::set_times(&out, day * days);
+
+        fingerprints.push(fingerprint);
+    }
+
+    let disk_size = |path: &PathBuf| DirInfo::dir_size(path).unwrap().disk_size;
+    let max_size = disk_size(target) - disk_size(&fingerprints[1]) / 2;
+
+    // a search first, which reads every folder
+    let params = WipeParams {
+        paths: vec![PathBuf::from(&test_run)],
+        languages: vec![LanguageEnum::Rust],
+        ..WipeParams::default()
+    };
+    Wipe::new(&mut Cursor::new(Vec::new()), &params)
+        .run()
+        .unwrap();
+
+    let params = WipeParams {
+        wipe: true,
+        rust_artifacts: Some(RustArtifacts::MaxSize(max_size)),
+        ..params
+    };
+
+    let mut buff = Cursor::new(Vec::new());
+    Wipe::new(&mut buff, &params).run().unwrap();
+
+    let output = std::str::from_utf8(buff.get_ref()).unwrap();
+    println!("{output}");
+
+    // the units are ranked by when their files were used
+    assert!(fingerprints[0].exists());
+    assert!(!fingerprints[1].exists());
+
+    let line = output
+        .lines()
+        .find(|l| l.ends_with("  debug (1 least recently used crate)"))
+        .unwrap();
+    assert!(line.contains("400 days ago"));
+}
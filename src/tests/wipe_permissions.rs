@@ -1,15 +1,32 @@
 #[cfg(target_os = "linux")]
 mod wipe_permissions_tests {
-    use std::io::Cursor;
+    use std::io::{Cursor, Write};
     use std::path::PathBuf;
 
     use rstest::rstest;
 
     use crate::command::LanguageEnum;
     use crate::tests::helpers::test_run::TestRun;
-    use crate::wipe::Wipe;
+    use crate::wipe::{RunStatus, Wipe};
     use crate::wipe_params::WipeParams;
 
+    /// The permissions don't stop root, which CI containers often run as, so the tests
+    /// are skipped then, saying so in their output.
+    fn skip_as_root() -> bool {
+        // SAFETY: `geteuid` has no preconditions and always succeeds
+        let is_root = unsafe { libc::geteuid() == 0 };
+
+        // written to stderr directly, since the output of passing tests is captured
+        if is_root {
+            let _ = writeln!(
+                std::io::stderr(),
+                "skipped, since the permissions don't stop root"
+            );
+        }
+
+        is_root
+    }
+
     #[rstest]
     #[case(LanguageEnum::Node, false)]
     #[case(LanguageEnum::Node, true)]
@@ -21,13 +38,18 @@ mod wipe_permissions_tests {
         use std::fs;
         use std::os::unix::fs::PermissionsExt;
 
+        if skip_as_root() {
+            return;
+        }
+
         let test_run = TestRun::new(&language, 3, 0);
 
         let params = WipeParams {
             wipe,
-            path: PathBuf::from(&test_run),
-            language,
+            paths: vec![PathBuf::from(&test_run)],
+            languages: vec![language],
             ignores: Vec::new(),
+            ..WipeParams::default()
         };
 
         let first_hit = test_run.hits.first().unwrap().clone();
@@ -37,11 +59,18 @@ mod wipe_permissions_tests {
         fs::set_permissions(first_hit_parent, permissions).unwrap();
 
         let mut buff = Cursor::new(Vec::new());
-        Wipe::new(&mut buff, &params).run().unwrap();
+        let status = Wipe::new(&mut buff, &params).run().unwrap();
 
         let output = std::str::from_utf8(buff.get_ref()).unwrap();
         println!("{output}");
 
+        let expected = if wipe {
+            RunStatus::WipeErrors
+        } else {
+            RunStatus::Found
+        };
+        assert_eq!(status, expected);
+
         // hits should be listed
         // and wiped if wipe is true and delete permissions are present
         for (i, path) in test_run.hits.iter().enumerate() {
@@ -55,4 +84,59 @@ mod wipe_permissions_tests {
         let permissions = fs::Permissions::from_mode(0o777);
         fs::set_permissions(first_hit_parent, permissions).unwrap();
     }
+
+    #[rstest]
+    #[case(false)]
+    #[case(true)]
+    fn test_with_unreadable_folders(#[case] strict: bool) {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        if skip_as_root() {
+            return;
+        }
+
+        let test_run = TestRun::new(&LanguageEnum::Rust, 3, 0);
+
+        // an unreadable folder next to the hits, and another one inside a hit
+        let unreadable = test_run.path.join("unreadable");
+        let nested = test_run.hits[0].join("nested");
+
+        for path in [&unreadable, &nested] {
+            fs::create_dir_all(path).unwrap();
+            fs::set_permissions(path, fs::Permissions::from_mode(0o000)).unwrap();
+        }
+
+        let params = WipeParams {
+            paths: vec![PathBuf::from(&test_run)],
+            languages: vec![LanguageEnum::Rust],
+            strict,
+            ..WipeParams::default()
+        };
+
+        let mut buff = Cursor::new(Vec::new());
+        let status = Wipe::new(&mut buff, &params).run().unwrap();
+
+        let output = std::str::from_utf8(buff.get_ref()).unwrap();
+        println!("{output}");
+
+        // every error is reported, and the other folders are still searched
+        assert!(output.contains("Could not scan"));
+
+        for path in [&unreadable, &nested] {
+            assert!(output.contains(path.to_str().unwrap()));
+        }
+
+        assert_eq!(status == RunStatus::ScanErrors, strict);
+
+        for path in &test_run.hits {
+            let expected = format!("    {}", path.display());
+            assert_eq!(output.lines().any(|l| l.ends_with(&expected)), !strict);
+        }
+
+        // revert the permissions change for cleanup
+        for path in [&unreadable, &nested] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o777)).unwrap();
+        }
+    }
 }
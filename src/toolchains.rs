@@ -0,0 +1,320 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs, io};
+
+use crate::rust_artifacts;
+
+/// The folder rustup installs the toolchains in, which is `$RUSTUP_HOME` (defaulting
+/// to `~/.rustup`).
+pub fn rustup_home() -> Option<PathBuf> {
+    env::var_os("RUSTUP_HOME")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::home_dir().map(|home| home.join(".rustup")))
+}
+
+/// Lists the compiler versions of the installed toolchains, as written by `rustc -V`
+/// without the leading `rustc` (e.g. `1.95.0 (59807616e 2026-04-14)`).
+///
+/// The version is read from the manifest rustup keeps for every toolchain, or else
+/// from running its `rustc`, which is needed for linked toolchains.
+pub fn installed(rustup_home: &Path) -> io::Result<Vec<String>> {
+    let mut versions = Vec::new();
+
+    for entry in fs::read_dir(rustup_home.join("toolchains"))? {
+        let toolchain = entry?.path();
+
+        if let Some(version) = manifest_version(&toolchain).or_else(|| rustc_version(&toolchain)) {
+            versions.push(version);
+        }
+    }
+
+    versions.sort();
+    versions.dedup();
+
+    Ok(versions)
+}
+
+/// Reads the version of the compiler that last built the `target` folder, from the
+/// output of `rustc -vV` that Cargo caches in `.rustc_info.json`.
+pub fn current(target: &Path) -> Option<String> {
+    let content = fs::read_to_string(target.join(".rustc_info.json")).ok()?;
+    let info = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+
+    info.get("outputs")?
+        .as_object()?
+        .values()
+        .filter_map(|output| output.get("stdout")?.as_str())
+        .find(|stdout| stdout.contains("\nrelease: "))
+        .and_then(parse_version)
+}
+
+/// Groups the artifacts in the profile folder (e.g. `target/debug`) by the version of
+/// the compiler that built them.
+///
+/// Every unit Cargo builds has a fingerprint with a hash of its compiler, and the
+/// version behind that hash is read from the `.rmeta` files of the libraries built
+/// with it. Artifacts whose compiler can't be told are left out.
+pub fn artifacts_by_version(profile: &Path) -> BTreeMap<String, Vec<PathBuf>> {
+    let units = rust_artifacts::units(profile)
+        .into_values()
+        .filter_map(|paths| Some((fingerprint_compiler(&paths)?, paths)))
+        .collect::<Vec<_>>();
+
+    // the version behind every compiler hash
+    let mut versions = HashMap::new();
+    for (compiler, paths) in &units {
+        if versions.contains_key(compiler) {
+            continue;
+        }
+
+        let version = paths
+            .iter()
+            .filter(|path| path.extension().is_some_and(|e| e == "rmeta"))
+            .find_map(|path| rmeta_version(path));
+
+        if let Some(version) = version {
+            versions.insert(*compiler, version);
+        }
+    }
+
+    let mut artifacts = BTreeMap::<String, Vec<PathBuf>>::new();
+    for (compiler, mut paths) in units {
+        if let Some(version) = versions.get(&compiler) {
+            artifacts
+                .entry(version.clone())
+                .or_default()
+                .append(&mut paths);
+        }
+    }
+
+    artifacts
+}
+
+fn manifest_version(toolchain: &Path) -> Option<String> {
+    let manifest = toolchain
+        .join("lib")
+        .join("rustlib")
+        .join("multirust-channel-manifest.toml");
+    let content = fs::read_to_string(manifest).ok()?;
+
+    // the manifest is large, so only the `version` of the `[pkg.rustc]` table is looked for
+    content
+        .lines()
+        .skip_while(|line| line.trim() != "[pkg.rustc]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim().trim_matches('"').to_string())
+}
+
+fn rustc_version(toolchain: &Path) -> Option<String> {
+    let output = Command::new(toolchain.join("bin").join("rustc"))
+        .arg("-V")
+        .output()
+        .ok()?;
+
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses the first line written by `rustc -V`, e.g. `rustc 1.95.0 (59807616e 2026-04-14)`.
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .strip_prefix("rustc ")
+        .map(|version| version.trim().to_string())
+}
+
+/// Reads the compiler version that is written at the start of every `.rmeta` file.
+fn rmeta_version(path: &Path) -> Option<String> {
+    let mut header = Vec::with_capacity(256);
+    fs::File::open(path)
+        .ok()?
+        .take(256)
+        .read_to_end(&mut header)
+        .ok()?;
+
+    let start = header.windows(6).position(|w| w == b"rustc ")?;
+    let version = header[start..]
+        .iter()
+        .take_while(|b| b.is_ascii_graphic() || **b == b' ')
+        .copied()
+        .collect::<Vec<_>>();
+
+    let version = String::from_utf8(version).ok()?;
+    let version = match version.find(')') {
+        Some(end) => &version[..=end],
+        None => &version,
+    };
+
+    parse_version(version)
+}
+
+/// Reads the compiler hash from the fingerprint of the unit, in which every JSON file
+/// has a `rustc` field.
+fn fingerprint_compiler(paths: &[PathBuf]) -> Option<u64> {
+    let fingerprint = paths.iter().find(|path| {
+        path.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == ".fingerprint")
+    })?;
+
+    rust_artifacts::entries(fingerprint)
+        .iter()
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .find_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            let fingerprint = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+
+            fingerprint.get("rustc")?.as_u64()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rstest::rstest;
+
+    use crate::tests::helpers::test_run::TestRun;
+    use crate::toolchains;
+
+    #[rstest]
+    #[case(
+        "rustc 1.95.0 (59807616e 2026-04-14)\n",
+        Some("1.95.0 (59807616e 2026-04-14)")
+    )]
+    #[case(
+        "rustc 1.97.0-nightly (e50aa6fba 2026-05-19)",
+        Some("1.97.0-nightly (e50aa6fba 2026-05-19)")
+    )]
+    #[case("cargo 1.95.0", None)]
+    #[case("", None)]
+    fn test_parse_version(#[case] output: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            toolchains::parse_version(output),
+            expected.map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_artifacts_by_version() {
+        let test_run = TestRun::empty();
+        let root = &test_run.path;
+
+        let rustup_home = root.join("rustup");
+        let manifest_dir = rustup_home
+            .join("toolchains")
+            .join("stable-x86_64-unknown-linux-gnu")
+            .join("lib")
+            .join("rustlib");
+        std::fs::create_dir_all(&manifest_dir).unwrap();
+        std::fs::write(
+            manifest_dir.join("multirust-channel-manifest.toml"),
+            "[pkg.cargo]\nversion = \"1.95.0 (f2932c5d8 2026-04-10)\"\n\n[pkg.rustc]\nversion = \"1.95.0 (59807616e 2026-04-14)\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            toolchains::installed(&rustup_home).unwrap(),
+            vec![String::from("1.95.0 (59807616e 2026-04-14)")]
+        );
+
+        let target = root.join("target");
+        let profile = target.join("debug");
+        let units = [
+            (
+                "old",
+                "1111111111111111",
+                1,
+                "1.90.0 (1159e78c4 2025-09-14)",
+            ),
+            (
+                "new",
+                "2222222222222222",
+                2,
+                "1.95.0 (59807616e 2026-04-14)",
+            ),
+        ];
+
+        for (name, hash, compiler, version) in units {
+            let fingerprint = profile.join(".fingerprint").join(format!("{name}-{hash}"));
+            std::fs::create_dir_all(&fingerprint).unwrap();
+            std::fs::write(
+                fingerprint.join(format!("lib-{name}.json")),
+                format!("{{\"rustc\":{compiler},\"features\":\"[]\"}}"),
+            )
+            .unwrap();
+
+            let deps = profile.join("deps");
+            std::fs::create_dir_all(&deps).unwrap();
+            let mut rmeta = b"rust\0\0\0\x09\0\0\0\0\0\0\0\0\x24".to_vec();
+            rmeta.extend_from_slice(format!("rustc {version}").as_bytes());
+            rmeta.extend_from_slice(b"\0\x01\x02");
+            std::fs::write(deps.join(format!("lib{name}-{hash}.rmeta")), rmeta).unwrap();
+            std::fs::write(deps.join(format!("{name}-{hash}.d")), "").unwrap();
+        }
+
+        // a build script unit has no library, but shares the compiler of the others
+        let build_script = profile.join(".fingerprint").join("script-3333333333333333");
+        std::fs::create_dir_all(&build_script).unwrap();
+        std::fs::write(
+            build_script.join("build-script-build-script-build.json"),
+            "{\"rustc\":1}",
+        )
+        .unwrap();
+        std::fs::create_dir_all(profile.join("build").join("script-3333333333333333")).unwrap();
+
+        // an unknown compiler is left out
+        let unknown = profile
+            .join(".fingerprint")
+            .join("unknown-4444444444444444");
+        std::fs::create_dir_all(&unknown).unwrap();
+        std::fs::write(unknown.join("lib-unknown.json"), "{\"rustc\":3}").unwrap();
+
+        let artifacts = toolchains::artifacts_by_version(&profile);
+        let names = |version: &str| {
+            artifacts[version]
+                .iter()
+                .map(|p| p.strip_prefix(&profile).unwrap().to_path_buf())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(
+            names("1.90.0 (1159e78c4 2025-09-14)"),
+            [
+                ".fingerprint/old-1111111111111111",
+                "deps/libold-1111111111111111.rmeta",
+                "deps/old-1111111111111111.d",
+                ".fingerprint/script-3333333333333333",
+                "build/script-3333333333333333",
+            ]
+            .map(PathBuf::from)
+        );
+        assert_eq!(
+            names("1.95.0 (59807616e 2026-04-14)"),
+            [
+                ".fingerprint/new-2222222222222222",
+                "deps/libnew-2222222222222222.rmeta",
+                "deps/new-2222222222222222.d",
+            ]
+            .map(PathBuf::from)
+        );
+
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(
+            target.join(".rustc_info.json"),
+            r#"{"rustc_fingerprint":1,"outputs":{"2":{"success":true,"status":"","code":0,"stdout":"rustc 1.95.0 (59807616e 2026-04-14)\nbinary: rustc\nrelease: 1.95.0\n","stderr":""}},"successes":{}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            toolchains::current(&target),
+            Some(String::from("1.95.0 (59807616e 2026-04-14)"))
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The home trash, as described by the freedesktop.org trash specification
+/// (https://specifications.freedesktop.org/trash-spec/).
+///
+/// Every trashed folder is moved into `files`, next to a `.trashinfo` file in `info`
+/// that records its original path and the deletion date, so that it can be restored
+/// by any compliant file manager.
+#[derive(Debug)]
+pub struct Trash {
+    files: PathBuf,
+    info: PathBuf,
+}
+
+impl Trash {
+    /// Opens the trash in `$XDG_DATA_HOME/Trash`, or `~/.local/share/Trash`
+    /// when `$XDG_DATA_HOME` is not set, creating it if needed.
+    pub fn home() -> io::Result<Self> {
+        let path =
+            Trash::home_path(env::var_os("XDG_DATA_HOME"), env::home_dir()).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Could not find the trash, since neither XDG_DATA_HOME nor HOME are set",
+                )
+            })?;
+
+        Trash::new(path)
+    }
+
+    /// Opens the trash in the given folder, creating it if needed.
+    pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let trash = Self {
+            files: path.join("files"),
+            info: path.join("info"),
+        };
+
+        Trash::create_dir(&trash.files)?;
+        Trash::create_dir(&trash.info)?;
+
+        Ok(trash)
+    }
+
+    fn home_path(xdg_data_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+        // relative paths are invalid and must be ignored, as per the XDG base directory spec
+        let data_home = xdg_data_home
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| home.map(|home| home.join(".local").join("share")))?;
+
+        Some(data_home.join("Trash"))
+    }
+
+    #[cfg(unix)]
+    fn create_dir(path: &Path) -> io::Result<()> {
+        use std::os::unix::fs::DirBuilderExt;
+
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(path)
+    }
+
+    #[cfg(not(unix))]
+    fn create_dir(path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    /// Moves the folder to the trash, and returns its path inside the trash.
+    ///
+    /// Folders on another device than the trash are copied and then deleted.
+    pub fn move_to_trash(&self, path: &Path, now: SystemTime) -> io::Result<PathBuf> {
+        let path = std::path::absolute(path)?;
+        let (name, mut info_file) = self.reserve_name(&path)?;
+        let info_path = self
+            .info
+            .join(format!("{}.trashinfo", name.to_string_lossy()));
+        let trashed = self.files.join(&name);
+
+        let result = info_file
+            .write_all(Trash::trash_info(&path, now)?.as_bytes())
+            .and_then(|_| info_file.sync_all())
+            .and_then(|_| Trash::move_dir(&path, &trashed));
+
+        if let Err(e) = result {
+            let _ = fs::remove_file(&info_path);
+            return Err(e);
+        }
+
+        Ok(trashed)
+    }
+
+    /// Picks a name that is not in use yet, by atomically creating its `.trashinfo` file.
+    fn reserve_name(&self, path: &Path) -> io::Result<(OsString, fs::File)> {
+        let base = path.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: cannot be moved to the trash", path.display()),
+            )
+        })?;
+
+        for index in 1.. {
+            let mut name = base.to_os_string();
+            if index > 1 {
+                name.push(format!(".{index}"));
+            }
+
+            let mut info_name = name.clone();
+            info_name.push(".trashinfo");
+
+            if self.files.join(&name).symlink_metadata().is_ok() {
+                continue;
+            }
+
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.info.join(&info_name))
+            {
+                Ok(file) => return Ok((name, file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        unreachable!()
+    }
+
+    fn trash_info(path: &Path, now: SystemTime) -> io::Result<String> {
+        Ok(format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            Trash::encode_path(path),
+            Trash::deletion_date(now)?
+        ))
+    }
+
+    fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+        match fs::rename(from, to) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                // files are moved on their own when only parts of a folder are wiped
+                if fs::symlink_metadata(from)?.is_file() {
+                    fs::copy(from, to)?;
+                    return fs::remove_file(from);
+                }
+
+                if let Err(e) = Trash::copy_dir(from, to) {
+                    let _ = fs::remove_dir_all(to);
+                    return Err(e);
+                }
+
+                fs::remove_dir_all(from)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Copies the folder recursively, keeping symlinks as they are.
+    fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir(to)?;
+
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let target = to.join(entry.file_name());
+
+            if file_type.is_dir() {
+                Trash::copy_dir(&entry.path(), &target)?;
+            } else if file_type.is_symlink() {
+                Trash::copy_symlink(&entry.path(), &target)?;
+            } else {
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+
+        fs::set_permissions(to, fs::metadata(from)?.permissions())
+    }
+
+    #[cfg(unix)]
+    fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)
+    }
+
+    #[cfg(not(unix))]
+    fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+        fs::copy(from, to).map(|_| ())
+    }
+
+    /// Percent-encodes the path as a URI path, as required for the `Path` key.
+    fn encode_path(path: &Path) -> String {
+        let mut encoded = String::new();
+
+        for byte in Trash::path_bytes(path) {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                    encoded.push(byte as char)
+                }
+                byte => encoded.push_str(&format!("%{byte:02X}")),
+            }
+        }
+
+        encoded
+    }
+
+    #[cfg(unix)]
+    fn path_bytes(path: &Path) -> Vec<u8> {
+        use std::os::unix::ffi::OsStrExt;
+
+        path.as_os_str().as_bytes().to_vec()
+    }
+
+    #[cfg(not(unix))]
+    fn path_bytes(path: &Path) -> Vec<u8> {
+        path.to_string_lossy().replace('\\', "/").into_bytes()
+    }
+
+    /// Formats the date as `YYYY-MM-DDThh:mm:ss` in the local time zone.
+    #[cfg(unix)]
+    fn deletion_date(now: SystemTime) -> io::Result<String> {
+        let seconds = now
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .as_secs() as libc::time_t;
+
+        // SAFETY: `tm` is plain data that `localtime_r` fills in, and both pointers
+        // are valid for the duration of the call
+        let tm = unsafe {
+            let mut tm = std::mem::zeroed::<libc::tm>();
+            if libc::localtime_r(&seconds, &mut tm).is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            tm
+        };
+
+        Ok(format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday,
+            tm.tm_hour,
+            tm.tm_min,
+            tm.tm_sec,
+        ))
+    }
+
+    #[cfg(not(unix))]
+    fn deletion_date(_now: SystemTime) -> io::Result<String> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "The trash is only supported on Unix-like systems",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::SystemTime;
+
+    use rstest::rstest;
+
+    use crate::tests::helpers::test_run::TestRun;
+    use crate::trash::Trash;
+
+    #[rstest]
+    #[case(Some("/data"), Some("/home/user"), Some("/data/Trash"))]
+    #[case(
+        Some("relative"),
+        Some("/home/user"),
+        Some("/home/user/.local/share/Trash")
+    )]
+    #[case(None, Some("/home/user"), Some("/home/user/.local/share/Trash"))]
+    #[case(None, None, None)]
+    fn test_home_path(
+        #[case] xdg_data_home: Option<&str>,
+        #[case] home: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            Trash::home_path(xdg_data_home.map(Into::into), home.map(PathBuf::from)),
+            expected.map(PathBuf::from)
+        );
+    }
+
+    #[rstest]
+    #[case("/work/app/target", "/work/app/target")]
+    #[case("/work/my app/node_modules", "/work/my%20app/node_modules")]
+    #[case("/work/50%/ü", "/work/50%25/%C3%BC")]
+    fn test_encode_path(#[case] path: &str, #[case] encoded: &str) {
+        assert_eq!(Trash::encode_path(Path::new(path)), encoded);
+    }
+
+    #[test]
+    fn test_move_to_trash() {
+        let test_run = TestRun::empty();
+        let base = &test_run.path;
+
+        let trash = Trash::new(base.join("Trash")).unwrap();
+
+        for index in 1..=2 {
+            let target = base.join(format!("app-{index}")).join("target");
+            fs::create_dir_all(target.join("debug")).unwrap();
+            fs::write(target.join("debug").join("data"), "data").unwrap();
+
+            let trashed = trash.move_to_trash(&target, SystemTime::now()).unwrap();
+
+            let name = if index == 1 { "target" } else { "target.2" };
+            assert_eq!(trashed, base.join("Trash").join("files").join(name));
+            assert!(!target.exists());
+            assert!(trashed.join("debug").join("data").exists());
+
+            let info = base
+                .join("Trash")
+                .join("info")
+                .join(format!("{name}.trashinfo"));
+            let info = fs::read_to_string(info).unwrap();
+            let mut lines = info.lines();
+
+            assert_eq!(lines.next(), Some("[Trash Info]"));
+            assert_eq!(
+                lines.next().map(String::from),
+                Some(format!("Path={}", Trash::encode_path(&target)))
+            );
+            assert!(lines.next().unwrap().starts_with("DeletionDate="));
+        }
+    }
+
+    #[test]
+    fn test_copy_dir() {
+        let test_run = TestRun::empty();
+        let base = &test_run.path;
+
+        let from = base.join("from");
+        fs::create_dir_all(from.join("nested")).unwrap();
+        fs::write(from.join("nested").join("data"), "data").unwrap();
+
+        Trash::copy_dir(&from, &base.join("to")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(base.join("to").join("nested").join("data")).unwrap(),
+            "data"
+        );
+    }
+}
@@ -0,0 +1,589 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::SystemTime;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+
+use crate::dir_helpers::{DirInfo, PathStatus, PathToDelete};
+use crate::writer::{SPACING_FILES, SPACING_LAST_USED, SPACING_PATH, SPACING_SIZE};
+
+/// The rows above and below the list: the title, the column names, and the help line.
+const CHROME_HEIGHT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortEnum {
+    Size,
+    Files,
+    LastUsed,
+    Path,
+}
+
+impl SortEnum {
+    fn next(self) -> Self {
+        match self {
+            SortEnum::Size => SortEnum::Files,
+            SortEnum::Files => SortEnum::LastUsed,
+            SortEnum::LastUsed => SortEnum::Path,
+            SortEnum::Path => SortEnum::Size,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortEnum::Size => "size",
+            SortEnum::Files => "files",
+            SortEnum::LastUsed => "last used",
+            SortEnum::Path => "path",
+        }
+    }
+}
+
+/// What the user decided once the TUI is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Confirmed,
+    Quit,
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// The position of the folder in the list that was passed in
+    index: usize,
+    path: String,
+    dir_info: Option<DirInfo>,
+    status: PathStatus,
+    selected: bool,
+}
+
+/// The state of the TUI, which lists the folders found and lets the user pick which
+/// ones to wipe.
+///
+/// The folders that would be wiped without the TUI start out selected. Ignored and
+/// protected folders, and the ones reached through a symlink, can't be selected,
+/// while the recent and filtered out ones can.
+#[derive(Debug)]
+pub struct App {
+    entries: Vec<Entry>,
+    cursor: usize,
+    sort: SortEnum,
+    reverse: bool,
+    confirming: bool,
+    /// Whether the selection is wiped once confirmed, or only listed in a dry run
+    wipe: bool,
+    now: SystemTime,
+}
+
+impl App {
+    pub fn new<'p>(
+        paths: impl IntoIterator<Item = (&'p PathToDelete, PathStatus)>,
+        wipe: bool,
+    ) -> Self {
+        let entries = paths
+            .into_iter()
+            .enumerate()
+            .map(|(index, (path_to_delete, status))| Entry {
+                index,
+                path: path_to_delete.path.clone(),
+                dir_info: path_to_delete.dir_info.as_ref().ok().copied(),
+                status,
+                selected: status == PathStatus::Selected,
+            })
+            .collect();
+
+        let mut app = Self {
+            entries,
+            cursor: 0,
+            sort: SortEnum::Size,
+            reverse: false,
+            confirming: false,
+            wipe,
+            now: SystemTime::now(),
+        };
+        app.sort_entries();
+        app.cursor = 0;
+
+        app
+    }
+
+    /// The statuses of the folders, in the order they were passed in. Selected folders
+    /// become `Selected`, and the ones that were deselected become `Declined`.
+    pub fn statuses(&self) -> Vec<PathStatus> {
+        let mut statuses = vec![PathStatus::Declined; self.entries.len()];
+
+        for entry in &self.entries {
+            statuses[entry.index] = match (entry.selected, entry.status) {
+                (true, _) => PathStatus::Selected,
+                (false, PathStatus::Selected) => PathStatus::Declined,
+                (false, status) => status,
+            };
+        }
+
+        statuses
+    }
+
+    /// The number and total size of the selected folders.
+    pub fn selected(&self) -> (usize, usize) {
+        self.entries
+            .iter()
+            .filter(|e| e.selected)
+            .fold((0, 0), |(count, size), e| {
+                (count + 1, size + e.dir_info.map_or(0, |i| i.size))
+            })
+    }
+
+    /// Handles a key press, and returns the outcome once the TUI should be closed.
+    /// Ctrl+C quits, since it doesn't interrupt the process in raw mode.
+    pub fn handle_key(&mut self, key: KeyEvent, page_size: usize) -> Option<Outcome> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Outcome::Quit);
+        }
+
+        if self.confirming {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => return Some(Outcome::Confirmed),
+                KeyCode::Char('n') | KeyCode::Esc => self.confirming = false,
+                _ => {}
+            }
+
+            return None;
+        }
+
+        let last = self.entries.len().saturating_sub(1);
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(page_size),
+            KeyCode::PageDown => self.cursor = (self.cursor + page_size).min(last),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = last,
+            KeyCode::Char(' ') => {
+                if let Some(entry) = self.entries.get_mut(self.cursor)
+                    && entry.status.is_selectable()
+                {
+                    entry.selected = !entry.selected;
+                }
+            }
+            KeyCode::Char('a') => self.select_all(true),
+            KeyCode::Char('n') => self.select_all(false),
+            KeyCode::Char('s') => {
+                self.sort = self.sort.next();
+                self.sort_entries();
+            }
+            KeyCode::Char('r') => {
+                self.reverse = !self.reverse;
+                self.sort_entries();
+            }
+            KeyCode::Enter => self.confirming = true,
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Outcome::Quit),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn select_all(&mut self, selected: bool) {
+        for entry in &mut self.entries {
+            if entry.status.is_selectable() {
+                entry.selected = selected;
+            }
+        }
+    }
+
+    /// Sorts the entries, largest, most files, or least recently used first,
+    /// keeping the cursor on the same folder.
+    fn sort_entries(&mut self) {
+        let current = self.entries.get(self.cursor).map(|e| e.index);
+
+        match self.sort {
+            SortEnum::Size => self
+                .entries
+                .sort_by_key(|e| std::cmp::Reverse(e.dir_info.map_or(0, |i| i.size))),
+            SortEnum::Files => self
+                .entries
+                .sort_by_key(|e| std::cmp::Reverse(e.dir_info.map_or(0, |i| i.file_count))),
+            SortEnum::LastUsed => self
+                .entries
+                .sort_by_key(|e| e.dir_info.and_then(|i| i.last_used)),
+            SortEnum::Path => self.entries.sort_by(|a, b| a.path.cmp(&b.path)),
+        }
+
+        if self.reverse {
+            self.entries.reverse();
+        }
+
+        if let Some(current) = current {
+            self.cursor = self
+                .entries
+                .iter()
+                .position(|e| e.index == current)
+                .unwrap_or(0);
+        }
+    }
+
+    fn title(&self) -> String {
+        let (count, size) = self.selected();
+
+        format!(
+            "cargo wipe - {} folders found, {count} selected ({}) - sorted by {}{}",
+            self.entries.len(),
+            DirInfo::new(0, 0, size).size_formatted_flex(),
+            self.sort.label(),
+            if self.reverse { " (reversed)" } else { "" },
+        )
+    }
+
+    fn help(&self) -> String {
+        if self.confirming {
+            let (count, size) = self.selected();
+            let size = DirInfo::new(0, 0, size).size_formatted_flex();
+
+            if self.wipe {
+                format!("Wipe {count} folders ({size})? [y]es, [n]o")
+            } else {
+                format!("List {count} folders ({size}) without wiping them (dry run)? [y]es, [n]o")
+            }
+        } else {
+            let enter = if self.wipe { "wipe" } else { "list" };
+
+            format!(
+                "↑↓ move  space toggle  a all  n none  s sort  r reverse  enter {enter}  q quit"
+            )
+        }
+    }
+
+    fn row(&self, position: usize) -> String {
+        let entry = &self.entries[position];
+
+        let mark = match (entry.status, entry.selected) {
+            (status, _) if !status.is_selectable() => "[-]",
+            (_, true) => "[x]",
+            (_, false) => "[ ]",
+        };
+
+        let tag = match entry.status {
+            PathStatus::Ignored => " [Ignored]",
+            PathStatus::Protected => " [Protected]",
+            PathStatus::Symlinked => " [Symlinked]",
+            PathStatus::Recent => " [Recent]",
+            PathStatus::Filtered => " [Filtered out]",
+            PathStatus::Selected | PathStatus::Declined => "",
+        };
+
+        let (files, size, last_used) = match &entry.dir_info {
+            Some(info) => (
+                info.file_count_formatted(),
+                info.size_formatted_flex(),
+                info.last_used_formatted(self.now),
+            ),
+            None => ("?".into(), "?".into(), "?".into()),
+        };
+
+        format!(
+            "{mark}{files:>SPACING_FILES$}{size:>SPACING_SIZE$}{last_used:>SPACING_LAST_USED$}{:SPACING_PATH$}{}{tag}",
+            "", entry.path,
+        )
+    }
+
+    /// The first entry to show so that the cursor is always visible.
+    fn offset(&self, page_size: usize) -> usize {
+        (self.cursor + 1).saturating_sub(page_size.max(1))
+    }
+
+    fn draw(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let page_size = height.saturating_sub(CHROME_HEIGHT);
+        let fit = |line: String| line.chars().take(width).collect::<String>();
+
+        queue!(
+            out,
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            SetAttribute(Attribute::Bold),
+            Print(fit(self.title())),
+            SetAttribute(Attribute::Reset),
+            cursor::MoveTo(0, 1),
+            Print(fit(format!(
+                "   {:>SPACING_FILES$}{:>SPACING_SIZE$}{:>SPACING_LAST_USED$}{:SPACING_PATH$}Path",
+                "Files #", "Size", "Last used", ""
+            ))),
+        )?;
+
+        let offset = self.offset(page_size);
+
+        for (row, position) in (offset..self.entries.len()).take(page_size).enumerate() {
+            queue!(out, cursor::MoveTo(0, (row + 2) as u16))?;
+
+            if position == self.cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+
+            queue!(
+                out,
+                Print(fit(self.row(position))),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1) as u16),
+            SetAttribute(Attribute::Bold),
+            Print(fit(self.help())),
+            SetAttribute(Attribute::Reset),
+        )?;
+
+        out.flush()
+    }
+}
+
+/// Restores the terminal when dropped, even if the TUI fails.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        // the guard is created before switching screens, so that raw mode is disabled
+        // again if switching fails
+        let guard = Self;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Shows the TUI until the selection is confirmed or the user quits.
+pub fn run(app: &mut App) -> io::Result<Outcome> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--tui can only be used when stdin and stdout are a terminal",
+        ));
+    }
+
+    let _guard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+
+    loop {
+        let (width, height) = terminal::size()?;
+        app.draw(&mut stdout, width as usize, height as usize)?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let page_size = (height as usize).saturating_sub(CHROME_HEIGHT);
+
+            if let Some(outcome) = app.handle_key(key, page_size) {
+                return Ok(outcome);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::command::DirectoryEnum;
+    use crate::dir_helpers::{DirInfo, PathStatus, PathToDelete};
+    use crate::tui::{App, Outcome};
+
+    fn path(path: &str, size: usize, file_count: usize) -> PathToDelete {
+        PathToDelete {
+            path: path.to_string(),
+            directory: DirectoryEnum::Target,
+            ignored: false,
+            protected: false,
+            symlinked: false,
+            projects: Vec::new(),
+            parts: None,
+            dir_info: Ok(DirInfo::new(0, file_count, size)),
+        }
+    }
+
+    fn app(paths: &[PathToDelete], statuses: &[PathStatus]) -> App {
+        App::new(paths.iter().zip(statuses.iter().copied()), true)
+    }
+
+    #[test]
+    fn test_initial_selection() {
+        let paths = [
+            path("/a/target", 100, 1),
+            path("/b/target", 300, 3),
+            path("/c/target", 200, 2),
+            path("/d/target", 50, 1),
+        ];
+        let statuses = [
+            PathStatus::Selected,
+            PathStatus::Selected,
+            PathStatus::Ignored,
+            PathStatus::Recent,
+        ];
+
+        let app = app(&paths, &statuses);
+
+        // sorted by size, largest first
+        assert!(app.row(0).ends_with("/b/target"));
+        assert!(app.row(1).ends_with("/c/target [Ignored]"));
+        assert!(app.row(0).starts_with("[x]"));
+        assert!(app.row(1).starts_with("[-]"));
+
+        assert_eq!(app.selected(), (2, 400));
+        assert_eq!(app.statuses(), statuses);
+    }
+
+    #[test]
+    fn test_toggle_and_confirm() {
+        let paths = [
+            path("/a/target", 100, 1),
+            path("/b/target", 300, 3),
+            path("/c/target", 200, 2),
+            path("/d/target", 50, 1),
+        ];
+        let statuses = [
+            PathStatus::Selected,
+            PathStatus::Selected,
+            PathStatus::Ignored,
+            PathStatus::Recent,
+        ];
+
+        let mut app = app(&paths, &statuses);
+
+        // deselect /b, try to select the ignored /c, select the recent /d
+        assert_eq!(app.handle_key(KeyCode::Char(' ').into(), 10), None);
+        assert_eq!(app.handle_key(KeyCode::Down.into(), 10), None);
+        assert_eq!(app.handle_key(KeyCode::Char(' ').into(), 10), None);
+        assert_eq!(app.handle_key(KeyCode::End.into(), 10), None);
+        assert_eq!(app.handle_key(KeyCode::Char(' ').into(), 10), None);
+
+        assert_eq!(app.selected(), (2, 150));
+
+        // the confirmation can be cancelled
+        assert_eq!(app.handle_key(KeyCode::Enter.into(), 10), None);
+        assert!(app.help().starts_with("Wipe 2 folders"));
+        assert_eq!(app.handle_key(KeyCode::Char('n').into(), 10), None);
+        assert_eq!(app.handle_key(KeyCode::Enter.into(), 10), None);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('y').into(), 10),
+            Some(Outcome::Confirmed)
+        );
+
+        assert_eq!(
+            app.statuses(),
+            vec![
+                PathStatus::Selected,
+                PathStatus::Declined,
+                PathStatus::Ignored,
+                PathStatus::Selected,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_confirm_dry_run() {
+        let paths = [path("/a/target", 100, 1)];
+        let mut app = App::new(paths.iter().map(|p| (p, PathStatus::Selected)), false);
+
+        assert!(app.help().contains("enter list"));
+        assert_eq!(app.handle_key(KeyCode::Enter.into(), 10), None);
+        assert!(app.help().starts_with("List 1 folders"));
+        assert!(app.help().contains("(dry run)"));
+    }
+
+    #[test]
+    fn test_select_all_and_none() {
+        let paths = [
+            path("/a/target", 100, 1),
+            path("/b/target", 300, 3),
+            path("/c/target", 200, 2),
+        ];
+        let statuses = [
+            PathStatus::Filtered,
+            PathStatus::Ignored,
+            PathStatus::Protected,
+        ];
+
+        let mut app = app(&paths, &statuses);
+        assert_eq!(app.selected(), (0, 0));
+
+        app.handle_key(KeyCode::Char('a').into(), 10);
+        assert_eq!(app.selected(), (1, 100));
+
+        app.handle_key(KeyCode::Char('n').into(), 10);
+        assert_eq!(app.selected(), (0, 0));
+        assert_eq!(
+            app.handle_key(KeyCode::Char('q').into(), 10),
+            Some(Outcome::Quit)
+        );
+    }
+
+    #[test]
+    fn test_ctrl_c_quits() {
+        let paths = [path("/a/target", 100, 1)];
+        let mut app = app(&paths, &[PathStatus::Selected]);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        assert_eq!(app.handle_key(ctrl_c, 10), Some(Outcome::Quit));
+
+        // also while confirming
+        assert_eq!(app.handle_key(KeyCode::Enter.into(), 10), None);
+        assert_eq!(app.handle_key(ctrl_c, 10), Some(Outcome::Quit));
+
+        // without Ctrl, it is only a key
+        assert_eq!(app.handle_key(KeyCode::Char('c').into(), 10), None);
+    }
+
+    #[test]
+    fn test_sort_keeps_cursor() {
+        let paths = [
+            path("/a/target", 100, 3),
+            path("/b/target", 300, 1),
+            path("/c/target", 200, 2),
+        ];
+        let statuses = [PathStatus::Selected; 3];
+
+        let mut app = app(&paths, &statuses);
+        assert!(app.row(app.cursor).ends_with("/b/target"));
+
+        // by files
+        app.handle_key(KeyCode::Char('s').into(), 10);
+        assert!(app.row(0).ends_with("/a/target"));
+        assert!(app.row(app.cursor).ends_with("/b/target"));
+
+        // by files, reversed
+        app.handle_key(KeyCode::Char('r').into(), 10);
+        assert!(app.row(0).ends_with("/b/target"));
+        assert_eq!(app.cursor, 0);
+        assert!(app.title().ends_with("sorted by files (reversed)"));
+    }
+
+    #[test]
+    fn test_offset_keeps_cursor_visible() {
+        let paths = (0..20)
+            .map(|i| path(&format!("/{i:02}/target"), 20 - i, 1))
+            .collect::<Vec<_>>();
+        let statuses = [PathStatus::Selected; 20];
+
+        let mut app = app(&paths, &statuses);
+        assert_eq!(app.offset(5), 0);
+
+        app.handle_key(KeyCode::PageDown.into(), 5);
+        app.handle_key(KeyCode::PageDown.into(), 5);
+        assert_eq!(app.cursor, 10);
+        assert_eq!(app.offset(5), 6);
+
+        app.handle_key(KeyCode::End.into(), 5);
+        assert_eq!(app.offset(5), 15);
+    }
+}
@@ -1,17 +1,33 @@
 use std::fs;
 use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::SystemTime;
 
-use crate::dir_helpers::DirInfo;
+use rayon::prelude::*;
+
+use crate::cargo_config::CargoConfig;
+use crate::command::DirectoryEnum;
+use crate::dir_helpers::{
+    DirInfo, Hardlinks, PartToDelete, PathStatus, PathToDelete, SearchOptions,
+};
+use crate::disk_space::FsSpace;
+use crate::ignore_matcher::IgnoreMatcher;
+use crate::mounts;
+use crate::rust_artifacts::RustArtifacts;
+use crate::summary::Summary;
+use crate::trash::Trash;
+use crate::tui::{self, App, Outcome};
 use crate::wipe_params::WipeParams;
 use crate::writer::Writer;
 
-#[derive(Debug)]
 pub struct Wipe<'a, W>
 where
     W: io::Write,
 {
     writer: Writer<'a, W>,
     params: &'a WipeParams,
+    input: Option<&'a mut dyn io::BufRead>,
 }
 
 impl<'a, W> Wipe<'a, W>
@@ -19,69 +35,520 @@ where
     W: io::Write,
 {
     pub fn new(stdout: &'a mut W, params: &'a WipeParams) -> Self {
-        let writer = Writer::new(stdout);
+        let writer = Writer::new(stdout, params.format);
+
+        Self {
+            writer,
+            params,
+            input: None,
+        }
+    }
 
-        Self { writer, params }
+    /// Sets where the answers are read from when prompting with `--interactive`,
+    /// which is only allowed when it is a terminal.
+    pub fn with_input(mut self, input: &'a mut dyn io::BufRead) -> Self {
+        self.input = Some(input);
+        self
     }
 
-    pub fn run(&mut self) -> io::Result<()> {
+    pub fn run(&mut self) -> io::Result<RunStatus> {
         let writer = &mut self.writer;
         let params = self.params;
 
+        if params.interactive && self.input.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--interactive can only be used when stdin is a terminal",
+            ));
+        }
+
         writer.write_header(params)?;
 
-        let paths_to_delete = DirInfo::get_paths_to_delete(&params.path, &params.language)?;
-        let paths_to_delete = paths_to_delete
+        let now = SystemTime::now();
+
+        let mut summaries = params
+            .paths
             .iter()
-            .filter_map(|p| p.as_ref().ok())
+            .map(|p| Summary::new(p.display().to_string(), &params.languages))
             .collect::<Vec<_>>();
 
-        let previous_info = if paths_to_delete.is_empty() {
+        let directories = params.directories();
+        let skipped_mounts = mounts::pseudo_mounts();
+        let cargo_config = if directories.contains(&DirectoryEnum::Target) {
+            Some(CargoConfig::from_env()?)
+        } else {
             None
+        };
+
+        // the paths are searched in parallel, and so are the folders inside them
+        let roots = params
+            .paths
+            .par_iter()
+            .map(|path| {
+                let ignores = IgnoreMatcher::new(&params.ignores, path)?;
+                let options = SearchOptions {
+                    directories: &directories,
+                    ignores: &ignores,
+                    follow_symlinks: params.follow_symlinks,
+                    one_file_system: params.one_file_system,
+                    skipped_mounts: &skipped_mounts,
+                    min_depth: params.min_depth,
+                    max_depth: params.max_depth,
+                    cargo_config: cargo_config.as_ref(),
+                };
+                let result = DirInfo::search(path, &options);
+                let paths = result.paths;
+
+                // the totals are incomplete when the depth is limited
+                let previous_info = if paths.is_empty() || params.max_depth.is_some() {
+                    None
+                } else {
+                    Some(result.total)
+                };
+
+                Ok((paths, previous_info, result.errors))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut candidates = Vec::new();
+        let mut scan_errors = Vec::new();
+
+        for (root, (paths, previous_info, mut errors)) in roots.into_iter().enumerate() {
+            scan_errors.append(&mut errors);
+
+            // a target directory shared by projects in several paths is only listed once
+            let paths = paths
+                .into_iter()
+                .filter_map(|mut path_to_delete| {
+                    let Some(candidate) = candidates
+                        .iter_mut()
+                        .find(|c: &&mut Candidate| c.path_to_delete.path == path_to_delete.path)
+                    else {
+                        return Some(path_to_delete);
+                    };
+
+                    let projects = &mut candidate.path_to_delete.projects;
+                    projects.append(&mut path_to_delete.projects);
+                    projects.sort();
+                    projects.dedup();
+                    None
+                })
+                .collect::<Vec<_>>();
+
+            summaries[root].previous_info = previous_info;
+            summaries[root].wipe_info.dir_count = paths.len();
+
+            candidates.extend(paths.into_iter().map(|path_to_delete| {
+                let status = if path_to_delete.ignored {
+                    PathStatus::Ignored
+                } else if path_to_delete.protected {
+                    PathStatus::Protected
+                } else if path_to_delete.symlinked {
+                    PathStatus::Symlinked
+                } else if params.older_than.is_some_and(|age| {
+                    path_to_delete
+                        .dir_info
+                        .as_ref()
+                        .is_ok_and(|info| info.is_used_within(age, now))
+                }) {
+                    PathStatus::Recent
+                } else {
+                    PathStatus::Selected
+                };
+
+                Candidate {
+                    root,
+                    path_to_delete,
+                    status,
+                }
+            }));
+        }
+
+        if params.strict && !scan_errors.is_empty() {
+            writer.write_scan_errors(&scan_errors)?;
+            writer.write_strict_footer(&scan_errors)?;
+
+            return Ok(RunStatus::ScanErrors);
+        }
+
+        if let Some(rust_artifacts) = &params.rust_artifacts {
+            Self::select_parts(&mut candidates, rust_artifacts);
+        }
+
+        Self::filter(&mut candidates, params);
+
+        if params.tui {
+            let mut app = App::new(
+                candidates.iter().map(|c| (&c.path_to_delete, c.status)),
+                params.wipe,
+            );
+
+            match tui::run(&mut app)? {
+                Outcome::Confirmed => {
+                    for (candidate, status) in candidates.iter_mut().zip(app.statuses()) {
+                        candidate.status = status;
+                    }
+                }
+                Outcome::Quit => {
+                    for candidate in candidates.iter_mut() {
+                        if candidate.status == PathStatus::Selected {
+                            candidate.status = PathStatus::Declined;
+                        }
+                    }
+                }
+            }
+        }
+
+        let trash = if params.trash && params.wipe {
+            Some(Trash::home()?)
         } else {
+            None
+        };
+
+        // the free space is measured rather than derived from the sizes found,
+        // since other files may change, and trashed folders still take up space
+        let mut spaces = FsSpace::measure(
+            candidates
+                .iter()
+                .filter(|c| c.status == PathStatus::Selected)
+                .map(|c| Path::new(&c.path_to_delete.path)),
+        );
+
+        if candidates.iter().any(|c| c.status != PathStatus::Filtered) {
             writer.write_content_header()?;
-            Some(DirInfo::dir_size(&params.path)?)
+        }
+
+        let mut selected = 0;
+        let mut wipe_failed = false;
+
+        let mut answer = if params.interactive {
+            Answer::Yes
+        } else {
+            Answer::All
         };
 
-        let mut wipe_info = DirInfo::new(paths_to_delete.len(), 0, 0);
-        let mut ignore_info = DirInfo::new(0, 0, 0);
+        for candidate in candidates {
+            let Candidate {
+                root,
+                path_to_delete,
+                mut status,
+            } = candidate;
+            let path = &path_to_delete.path;
 
-        let paths_ignored = params
-            .ignores
-            .iter()
-            .map(|p| p.display().to_string().to_lowercase())
-            .collect::<Vec<_>>();
+            if status == PathStatus::Selected && params.wipe {
+                if let (Some(input), Answer::Yes | Answer::No) = (&mut self.input, answer) {
+                    answer = Self::prompt(writer, &mut **input, &path_to_delete)?;
+                }
 
-        for path in paths_to_delete {
-            let dir_info = DirInfo::dir_size(path);
+                if let Answer::No | Answer::Quit = answer {
+                    status = PathStatus::Declined;
+                }
+            }
 
-            let ignored = paths_ignored
-                .iter()
-                .any(|p| path.to_lowercase().starts_with(p));
+            let wipe = |path: &Path| {
+                DirInfo::check_no_symlinks(path).and_then(|_| match &trash {
+                    Some(trash) => trash.move_to_trash(path, now).map(|_| ()),
+                    None if path.is_file() => fs::remove_file(path),
+                    None => fs::remove_dir_all(path),
+                })
+            };
 
-            let error = if !ignored && params.wipe {
-                fs::remove_dir_all(path).err()
+            let error = if status == PathStatus::Selected && params.wipe {
+                match &path_to_delete.parts {
+                    // every part is wiped even when another one fails, and the first error is reported
+                    Some(parts) => parts
+                        .iter()
+                        .flat_map(|part| &part.paths)
+                        .map(|path| wipe(path))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .find_map(Result::err),
+                    None => wipe(Path::new(path)).err(),
+                }
             } else {
                 None
             };
 
-            if let Ok(dir_info) = dir_info {
-                if ignored {
-                    ignore_info.dir_count += 1;
-                    ignore_info.file_count += dir_info.file_count;
-                    ignore_info.size += dir_info.size;
-                } else if error.is_none() {
-                    wipe_info.file_count += dir_info.file_count;
-                    wipe_info.size += dir_info.size;
+            if status == PathStatus::Selected {
+                selected += 1;
+                wipe_failed |= error.is_some();
+            }
+
+            if let Ok(dir_info) = &path_to_delete.dir_info {
+                match status {
+                    PathStatus::Ignored => summaries[root].add_ignored(dir_info),
+                    PathStatus::Protected => summaries[root].add_protected(dir_info),
+                    PathStatus::Symlinked => summaries[root].add_symlinked(dir_info),
+                    PathStatus::Recent => summaries[root].add_recent(dir_info),
+                    PathStatus::Filtered => summaries[root].add_filtered(dir_info),
+                    PathStatus::Declined => summaries[root].add_declined(dir_info),
+                    PathStatus::Selected if error.is_none() => {
+                        summaries[root].add_wiped(path_to_delete.directory, dir_info);
+
+                        if trash.is_some() {
+                            summaries[root].add_trashed(dir_info);
+                        }
+                    }
+                    PathStatus::Selected => {}
+                }
+            }
+
+            writer.write_content_line(&path_to_delete, status, error)?;
+        }
+
+        if params.wipe {
+            for space in spaces.iter_mut() {
+                space.measure_after();
+            }
+        }
+
+        let total = if summaries.len() > 1 {
+            Summary::combine("Total", &summaries)
+        } else {
+            summaries[0].clone()
+        };
+
+        writer.write_scan_errors(&scan_errors)?;
+        writer.write_summary(params, &summaries, &total)?;
+        writer.write_disk_space(params, &spaces)?;
+        writer.write_footer(params, &total)?;
+
+        let status = if wipe_failed {
+            RunStatus::WipeErrors
+        } else if let Some(max_reclaimable) = params.max_reclaimable {
+            if total.wipe_info.disk_size > max_reclaimable {
+                RunStatus::OverBudget
+            } else {
+                RunStatus::Clean
+            }
+        } else if selected > 0 && !params.wipe {
+            RunStatus::Found
+        } else {
+            RunStatus::Clean
+        };
+
+        Ok(status)
+    }
+
+    /// Asks whether the folder should be wiped until a valid answer is given.
+    /// The end of the input is treated as quitting.
+    fn prompt(
+        writer: &mut Writer<'a, W>,
+        input: &mut dyn io::BufRead,
+        path_to_delete: &PathToDelete,
+    ) -> io::Result<Answer> {
+        loop {
+            writer.write_prompt(path_to_delete)?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(Answer::Quit);
+            }
+
+            match line.trim().to_lowercase().as_str() {
+                "y" | "yes" => return Ok(Answer::Yes),
+                "n" | "no" => return Ok(Answer::No),
+                "a" | "all" => return Ok(Answer::All),
+                "q" | "quit" => return Ok(Answer::Quit),
+                _ => {}
+            }
+        }
+    }
+
+    /// Narrows the `target` folders down to the parts to wipe with `--rust-only`,
+    /// `--rust-keep`, `--rust-toolchains`, `--rust-max-size` or `--rust-unlocked`, so that
+    /// they are sized and filtered by what is wiped. Selected folders with nothing to
+    /// wipe are filtered out.
+    ///
+    /// This includes the recent and filtered out folders, which can still be picked in
+    /// the TUI, so that only their parts are wiped then too.
+    fn select_parts(candidates: &mut [Candidate], rust_artifacts: &RustArtifacts) {
+        candidates
+            .par_iter_mut()
+            .filter(|c| {
+                c.status.is_selectable() && c.path_to_delete.directory == DirectoryEnum::Target
+            })
+            .for_each(|candidate| {
+                let path_to_delete = &mut candidate.path_to_delete;
+                let disk_size = path_to_delete.dir_info.as_ref().map_or(0, |i| i.disk_size);
+                let target = Path::new(&path_to_delete.path);
+
+                // a `target` folder that is not a custom one belongs to the project above it
+                let projects = match target.parent() {
+                    Some(project) if path_to_delete.projects.is_empty() => {
+                        vec![project.to_path_buf()]
+                    }
+                    _ => path_to_delete.projects.clone(),
+                };
+
+                let hardlinks = Hardlinks::default();
+                let parts = rust_artifacts
+                    .parts(target, &projects, disk_size)
+                    .into_iter()
+                    .map(|(name, paths)| {
+                        let dir_info =
+                            paths
+                                .iter()
+                                .try_fold(DirInfo::new(0, 0, 0), |mut acc, path| {
+                                    acc += DirInfo::dir_size_with(path, &hardlinks)?;
+                                    Ok(acc)
+                                });
+
+                        PartToDelete {
+                            name,
+                            paths,
+                            dir_info,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut dir_info = DirInfo::new(0, 0, 0);
+                for part in &parts {
+                    if let Ok(part_info) = &part.dir_info {
+                        dir_info += *part_info;
+                    }
+                }
+
+                if parts.is_empty() && candidate.status == PathStatus::Selected {
+                    candidate.status = PathStatus::Filtered;
+                }
+
+                path_to_delete.dir_info = Ok(dir_info);
+                path_to_delete.parts = Some(parts);
+            });
+    }
+
+    /// Marks the selected folders that are smaller than `--min-size`,
+    /// or not among the `--top` largest ones, as filtered out.
+    fn filter(candidates: &mut [Candidate], params: &WipeParams) {
+        let size = |candidate: &Candidate| {
+            candidate
+                .path_to_delete
+                .dir_info
+                .as_ref()
+                .map_or(0, |i| i.size)
+        };
+
+        if let Some(min_size) = params.min_size {
+            for candidate in candidates.iter_mut() {
+                if candidate.status == PathStatus::Selected && size(candidate) < min_size {
+                    candidate.status = PathStatus::Filtered;
                 }
             }
+        }
+
+        if let Some(top) = params.top {
+            let mut selected = candidates
+                .iter_mut()
+                .filter(|c| c.status == PathStatus::Selected)
+                .collect::<Vec<_>>();
 
-            writer.write_content_line(path, dir_info, ignored, error)?;
+            selected.sort_by_key(|c| std::cmp::Reverse(size(c)));
+
+            for candidate in selected.into_iter().skip(top) {
+                candidate.status = PathStatus::Filtered;
+            }
         }
+    }
+}
+
+/// How a run ended, which is reported as the exit code of the process. Errors that
+/// stop the run exit with code 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Nothing was found to wipe, everything selected was wiped, or `--check` passed
+    Clean = 0,
+    /// The arguments are invalid, like the ones rejected while parsing them, or can't be
+    /// used without a terminal
+    InvalidArguments = 2,
+    /// Folders were found to wipe in a dry run
+    Found = 3,
+    /// Some of the selected folders could not be wiped
+    WipeErrors = 4,
+    /// Something could not be scanned with `--strict`, so nothing was wiped
+    ScanErrors = 5,
+    /// More space than `--max-reclaimable` can be reclaimed with `--check`
+    OverBudget = 6,
+}
+
+impl From<RunStatus> for ExitCode {
+    fn from(status: RunStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// The answer to the last `--interactive` prompt.
+#[derive(Debug, Clone, Copy)]
+enum Answer {
+    Yes,
+    No,
+    /// Wipe this and all the remaining folders without asking again
+    All,
+    /// Keep this and all the remaining folders without asking again
+    Quit,
+}
+
+#[derive(Debug)]
+struct Candidate {
+    root: usize,
+    path_to_delete: PathToDelete,
+    status: PathStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crossterm::event::KeyCode;
+
+    use crate::command::{DirectoryEnum, LanguageEnum, RustArtifactEnum};
+    use crate::dir_helpers::{DirInfo, PathStatus, PathToDelete};
+    use crate::rust_artifacts::RustArtifacts;
+    use crate::tests::helpers::test_run::TestRun;
+    use crate::tui::App;
+    use crate::wipe::{Candidate, Wipe};
+
+    #[test]
+    fn test_select_parts_of_recent_target() {
+        let test_run = TestRun::new(&LanguageEnum::Rust, 1, 0);
+        let target = &test_run.hits[0];
+
+        for dir in ["debug/deps", "release/deps"] {
+            std::fs::create_dir_all(target.join(dir)).unwrap();
+            TestRun::fill(&target.join(dir), 1024);
+        }
+
+        let mut candidates = vec![Candidate {
+            root: 0,
+            path_to_delete: PathToDelete {
+                path: target.display().to_string(),
+                directory: DirectoryEnum::Target,
+                ignored: false,
+                protected: false,
+                symlinked: false,
+                projects: Vec::new(),
+                parts: None,
+                dir_info: DirInfo::dir_size(target),
+            },
+            status: PathStatus::Recent,
+        }];
+
+        Wipe::<io::Sink>::select_parts(
+            &mut candidates,
+            &RustArtifacts::Keep(vec![RustArtifactEnum::Release]),
+        );
+
+        // the recent folder is picked in the TUI
+        let mut app = App::new(
+            candidates.iter().map(|c| (&c.path_to_delete, c.status)),
+            true,
+        );
+        app.handle_key(KeyCode::Char(' ').into(), 10);
+        assert_eq!(app.statuses(), vec![PathStatus::Selected]);
 
-        writer.write_summary(params, &wipe_info, &ignore_info, &previous_info)?;
-        writer.write_footer(params, &wipe_info)?;
+        let parts = candidates[0].path_to_delete.parts.as_ref().unwrap();
+        let paths = parts.iter().flat_map(|p| &p.paths).collect::<Vec<_>>();
 
-        Ok(())
+        assert_eq!(paths, vec![&target.join("debug")]);
     }
 }
@@ -1,27 +1,250 @@
-use std::path::PathBuf;
-use std::{env, io};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{env, fs, io};
 
-use crate::command::{Args, LanguageEnum};
+use crate::command::{Args, DirectoryEnum, FormatEnum, LanguageEnum, StaleToolchainEnum};
+use crate::config::Config;
+use crate::rust_artifacts::RustArtifacts;
+use crate::toolchains;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct WipeParams {
     pub wipe: bool,
-    pub path: PathBuf,
-    pub language: LanguageEnum,
+    pub interactive: bool,
+    pub tui: bool,
+    pub trash: bool,
+    pub paths: Vec<PathBuf>,
+    pub languages: Vec<LanguageEnum>,
     pub ignores: Vec<PathBuf>,
+    pub follow_symlinks: bool,
+    pub one_file_system: bool,
+    pub min_depth: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub older_than: Option<Duration>,
+    pub min_size: Option<usize>,
+    pub top: Option<usize>,
+    /// The parts of the `target` folders to wipe, set with `--rust-only` or `--rust-keep`
+    pub rust_artifacts: Option<RustArtifacts>,
+    pub strict: bool,
+    /// The budget for the space that can be reclaimed, only set with `--check`
+    pub max_reclaimable: Option<usize>,
+    pub format: FormatEnum,
 }
 
 impl WipeParams {
-    pub fn new(args: &Args) -> io::Result<Self> {
-        let path = env::current_dir()?;
+    /// Merges the arguments with the config file, where the arguments take precedence,
+    /// except for the ignores, which are combined.
+    pub fn new(args: &Args, config: &Config) -> io::Result<Self> {
+        let format = args.format.or(config.format).unwrap_or_default();
+
+        if format == FormatEnum::Json && (args.interactive || args.tui) {
+            let flag = if args.tui { "--tui" } else { "--interactive" };
+
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{flag} cannot be used with --format json"),
+            ));
+        }
+
+        match (args.min_depth, args.max_depth) {
+            (_, Some(0)) | (Some(0), _) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--min-depth and --max-depth must be at least 1",
+                ));
+            }
+            (Some(min), Some(max)) if min > max => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--min-depth cannot be greater than --max-depth",
+                ));
+            }
+            _ => {}
+        }
+
+        let languages = if args.languages.is_empty() {
+            &config.languages
+        } else {
+            &args.languages
+        };
+
+        if languages.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No languages were given, neither as arguments nor in the config file",
+            ));
+        }
+
+        let languages = LanguageEnum::expand(languages);
+
+        let rust_artifacts = match (
+            args.rust_only.is_empty(),
+            args.rust_keep.is_empty(),
+            args.rust_toolchains,
+            args.rust_max_size,
+            args.rust_unlocked,
+        ) {
+            (true, true, None, None, false) => None,
+            (false, true, None, None, false) => Some(RustArtifacts::Only(args.rust_only.clone())),
+            (true, false, None, None, false) => Some(RustArtifacts::Keep(args.rust_keep.clone())),
+            (true, true, Some(StaleToolchainEnum::Uninstalled), None, false) => Some(
+                RustArtifacts::Uninstalled(WipeParams::installed_toolchains()?),
+            ),
+            (true, true, Some(StaleToolchainEnum::NotCurrent), None, false) => {
+                Some(RustArtifacts::NotCurrent)
+            }
+            (true, true, None, Some(max_size), false) => Some(RustArtifacts::MaxSize(max_size)),
+            (true, true, None, None, true) => Some(RustArtifacts::Unlocked),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "--rust-only, --rust-keep, --rust-toolchains, --rust-max-size and --rust-unlocked cannot be used together",
+                ));
+            }
+        };
+
+        if rust_artifacts.is_some() && !languages.contains(&LanguageEnum::Rust) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--rust-only, --rust-keep, --rust-toolchains, --rust-max-size and --rust-unlocked can only be used when targeting rust",
+            ));
+        }
+
+        let paths = if args.paths.is_empty() && args.roots.is_empty() {
+            config.paths()
+        } else {
+            args.paths
+                .iter()
+                .chain(args.roots.iter())
+                .cloned()
+                .collect()
+        };
+
+        let paths = paths
+            .iter()
+            .map(|p| WipeParams::canonicalize(p))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let paths = if paths.is_empty() {
+            vec![env::current_dir()?]
+        } else {
+            WipeParams::drop_nested(paths)
+        };
 
         Ok(Self {
             wipe: args.wipe,
-            path,
-            language: args.language.clone(),
-            ignores: args.ignores.clone(),
+            interactive: args.interactive,
+            tui: args.tui,
+            trash: args.trash,
+            paths,
+            languages,
+            ignores: config
+                .ignores
+                .iter()
+                .chain(args.ignores.iter())
+                .cloned()
+                .collect(),
+            follow_symlinks: args.follow_symlinks,
+            one_file_system: args.one_file_system,
+            min_depth: args.min_depth,
+            max_depth: args.max_depth,
+            older_than: args.older_than.or(config.older_than()?),
+            min_size: args.min_size.or(config.min_size()?),
+            top: args.top,
+            rust_artifacts,
+            strict: args.strict,
+            max_reclaimable: args.max_reclaimable,
+            format,
         })
     }
+
+    /// Lists the versions of the installed toolchains, which must not be empty, since
+    /// everything would be seen as built by an uninstalled toolchain otherwise.
+    fn installed_toolchains() -> io::Result<Vec<String>> {
+        let error = |e: &dyn std::fmt::Display| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "--rust-toolchains uninstalled needs the toolchains installed with rustup: {e}"
+                ),
+            )
+        };
+
+        let rustup_home = toolchains::rustup_home().ok_or_else(|| error(&"no home directory"))?;
+        let installed = toolchains::installed(&rustup_home)
+            .map_err(|e| error(&format!("{}: {e}", rustup_home.display())))?;
+
+        if installed.is_empty() {
+            return Err(error(&format!(
+                "none were found in {}",
+                rustup_home.display()
+            )));
+        }
+
+        Ok(installed)
+    }
+
+    fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+    }
+
+    /// Removes the paths that are equal to or nested inside another path,
+    /// since they are already covered by searching the outer one.
+    fn drop_nested(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut kept: Vec<PathBuf> = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            if kept.iter().any(|k| path.starts_with(k)) {
+                continue;
+            }
+
+            kept.retain(|k| !k.starts_with(&path));
+            kept.push(path);
+        }
+
+        kept
+    }
+
+    /// The folders to search for, from the languages, without duplicates.
+    pub fn directories(&self) -> Vec<DirectoryEnum> {
+        let mut directories = Vec::new();
+
+        for directory in self.languages.iter().flat_map(LanguageEnum::directories) {
+            if !directories.contains(directory) {
+                directories.push(*directory);
+            }
+        }
+
+        directories
+    }
+
+    pub fn paths_formatted(&self) -> String {
+        self.paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Describes the depths that are searched, if they are limited.
+    pub fn depth_formatted(&self) -> Option<String> {
+        match (self.min_depth, self.max_depth) {
+            (Some(min), Some(max)) if min == max => Some(format!("at depth {min}")),
+            (Some(min), Some(max)) => Some(format!("at depths {min} to {max}")),
+            (Some(min), None) => Some(format!("from depth {min}")),
+            (None, Some(max)) => Some(format!("up to depth {max}")),
+            (None, None) => None,
+        }
+    }
+
+    pub fn languages_formatted(&self) -> String {
+        self.languages
+            .iter()
+            .map(LanguageEnum::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 #[cfg(test)]
@@ -30,30 +253,353 @@ mod tests {
 
     use rstest::rstest;
 
-    use crate::command::{Args, LanguageEnum};
+    use crate::command::{Args, FormatEnum, LanguageEnum, RustArtifactEnum, StaleToolchainEnum};
+    use crate::config::Config;
+    use crate::rust_artifacts::RustArtifacts;
+    use crate::tests::helpers::test_run::TestRun;
     use crate::wipe_params::WipeParams;
 
     #[rstest]
-    #[case(Args { language: LanguageEnum::Node, wipe: false, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Node, wipe: true, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Node, wipe: true, ignores: vec![PathBuf::from("example/path")] })]
-    #[case(Args { language: LanguageEnum::Rust, wipe: false, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Rust, wipe: true, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Rust, wipe: true, ignores: vec![PathBuf::from("example/path")] })]
-    #[case(Args { language: LanguageEnum::Terraform, wipe: false, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Terraform, wipe: true, ignores: Vec::new() })]
-    #[case(Args { language: LanguageEnum::Terraform, wipe: true, ignores: vec![PathBuf::from("example/path")] })]
+    #[case(Args { languages: vec![LanguageEnum::Node], wipe: false, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Node], wipe: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Node], wipe: true, ignores: vec![PathBuf::from("example/path")], ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: false, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, ignores: vec![PathBuf::from("example/path")], ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Terraform], wipe: false, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Terraform], wipe: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Terraform], wipe: true, ignores: vec![PathBuf::from("example/path")], ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, trash: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, interactive: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], tui: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], follow_symlinks: true, one_file_system: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], min_depth: Some(2), max_depth: Some(3), ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, strict: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], check: true, max_reclaimable: Some(1024), ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust, LanguageEnum::Node], wipe: false, ..Args::default() })]
     fn test_wipe_params(#[case] args: Args) {
-        let params = WipeParams::new(&args).unwrap();
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
 
         assert_eq!(
             params,
             WipeParams {
                 wipe: args.wipe,
-                path: std::env::current_dir().unwrap(),
-                language: args.language,
+                interactive: args.interactive,
+                tui: args.tui,
+                trash: args.trash,
+                paths: vec![std::env::current_dir().unwrap()],
+                languages: args.languages,
                 ignores: args.ignores,
+                follow_symlinks: args.follow_symlinks,
+                one_file_system: args.one_file_system,
+                min_depth: args.min_depth,
+                max_depth: args.max_depth,
+                older_than: args.older_than,
+                min_size: args.min_size,
+                top: args.top,
+                rust_artifacts: None,
+                strict: args.strict,
+                max_reclaimable: args.max_reclaimable,
+                format: args.format.unwrap_or_default(),
             }
         );
     }
+
+    #[rstest]
+    #[case(Args { languages: vec![LanguageEnum::Rust], wipe: true, interactive: true, format: Some(FormatEnum::Json), ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], tui: true, format: Some(FormatEnum::Json), ..Args::default() })]
+    fn test_wipe_params_prompts_with_json(#[case] args: Args) {
+        let error = WipeParams::new(&args, &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_wipe_params_with_config() {
+        let config = Config {
+            languages: vec![LanguageEnum::Rust],
+            paths: vec![std::env::temp_dir()],
+            ignores: vec![PathBuf::from("*-legacy")],
+            older_than: Some(String::from("30d")),
+            min_size: Some(String::from("1K")),
+            format: Some(FormatEnum::Json),
+            ..Config::default()
+        };
+
+        // the config is used when no arguments are given
+        let params = WipeParams::new(&Args::default(), &config).unwrap();
+
+        assert_eq!(params.languages, vec![LanguageEnum::Rust]);
+        assert_eq!(
+            params.paths,
+            vec![std::fs::canonicalize(std::env::temp_dir()).unwrap()]
+        );
+        assert_eq!(params.ignores, vec![PathBuf::from("*-legacy")]);
+        assert_eq!(
+            params.older_than,
+            Some(std::time::Duration::from_secs(30 * 24 * 60 * 60))
+        );
+        assert_eq!(params.min_size, Some(1024));
+        assert_eq!(params.format, FormatEnum::Json);
+
+        // the arguments override it, except for the ignores, which are combined
+        let args = Args {
+            languages: vec![LanguageEnum::Node],
+            paths: vec![std::env::current_dir().unwrap()],
+            ignores: vec![PathBuf::from("vendor")],
+            older_than: Some(std::time::Duration::from_secs(60)),
+            min_size: Some(1),
+            format: Some(FormatEnum::Text),
+            ..Args::default()
+        };
+        let params = WipeParams::new(&args, &config).unwrap();
+
+        assert_eq!(params.languages, vec![LanguageEnum::Node]);
+        assert_eq!(params.paths, vec![std::env::current_dir().unwrap()]);
+        assert_eq!(
+            params.ignores,
+            vec![PathBuf::from("*-legacy"), PathBuf::from("vendor")]
+        );
+        assert_eq!(params.older_than, Some(std::time::Duration::from_secs(60)));
+        assert_eq!(params.min_size, Some(1));
+        assert_eq!(params.format, FormatEnum::Text);
+    }
+
+    #[test]
+    fn test_wipe_params_without_languages() {
+        let error = WipeParams::new(&Args::default(), &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    #[case(vec![], vec![], None, None)]
+    #[case(
+        vec![RustArtifactEnum::Debug],
+        vec![],
+        None,
+        Some(RustArtifacts::Only(vec![RustArtifactEnum::Debug]))
+    )]
+    #[case(
+        vec![],
+        vec![RustArtifactEnum::Release],
+        None,
+        Some(RustArtifacts::Keep(vec![RustArtifactEnum::Release]))
+    )]
+    #[case(
+        vec![],
+        vec![],
+        Some(StaleToolchainEnum::NotCurrent),
+        Some(RustArtifacts::NotCurrent)
+    )]
+    fn test_wipe_params_rust_artifacts(
+        #[case] rust_only: Vec<RustArtifactEnum>,
+        #[case] rust_keep: Vec<RustArtifactEnum>,
+        #[case] rust_toolchains: Option<StaleToolchainEnum>,
+        #[case] expected: Option<RustArtifacts>,
+    ) {
+        let args = Args {
+            languages: vec![LanguageEnum::All],
+            rust_only,
+            rust_keep,
+            rust_toolchains,
+            ..Args::default()
+        };
+
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
+
+        assert_eq!(params.rust_artifacts, expected);
+    }
+
+    #[rstest]
+    #[case(None, None)]
+    #[case(Some(1024), Some(RustArtifacts::MaxSize(1024)))]
+    fn test_wipe_params_rust_max_size(
+        #[case] rust_max_size: Option<usize>,
+        #[case] expected: Option<RustArtifacts>,
+    ) {
+        let args = Args {
+            languages: vec![LanguageEnum::Rust],
+            rust_max_size,
+            ..Args::default()
+        };
+
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
+
+        assert_eq!(params.rust_artifacts, expected);
+    }
+
+    #[rstest]
+    #[case(LanguageEnum::Node, vec![RustArtifactEnum::Debug], vec![], None)]
+    #[case(LanguageEnum::Node, vec![], vec![], Some(StaleToolchainEnum::NotCurrent))]
+    #[case(
+        LanguageEnum::Rust,
+        vec![RustArtifactEnum::Debug],
+        vec![RustArtifactEnum::Release],
+        None
+    )]
+    #[case(
+        LanguageEnum::Rust,
+        vec![RustArtifactEnum::Debug],
+        vec![],
+        Some(StaleToolchainEnum::NotCurrent)
+    )]
+    fn test_wipe_params_invalid_rust_artifacts(
+        #[case] language: LanguageEnum,
+        #[case] rust_only: Vec<RustArtifactEnum>,
+        #[case] rust_keep: Vec<RustArtifactEnum>,
+        #[case] rust_toolchains: Option<StaleToolchainEnum>,
+    ) {
+        let args = Args {
+            languages: vec![language],
+            rust_only,
+            rust_keep,
+            rust_toolchains,
+            ..Args::default()
+        };
+
+        let error = WipeParams::new(&args, &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    #[case(LanguageEnum::Node, vec![])]
+    #[case(LanguageEnum::Rust, vec![RustArtifactEnum::Debug])]
+    fn test_wipe_params_invalid_rust_max_size(
+        #[case] language: LanguageEnum,
+        #[case] rust_only: Vec<RustArtifactEnum>,
+    ) {
+        let args = Args {
+            languages: vec![language],
+            rust_only,
+            rust_max_size: Some(1024),
+            ..Args::default()
+        };
+
+        let error = WipeParams::new(&args, &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_wipe_params_rust_unlocked() {
+        let args = Args {
+            languages: vec![LanguageEnum::Rust],
+            rust_unlocked: true,
+            ..Args::default()
+        };
+
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
+
+        assert_eq!(params.rust_artifacts, Some(RustArtifacts::Unlocked));
+    }
+
+    #[rstest]
+    #[case(Args { languages: vec![LanguageEnum::Node], rust_unlocked: true, ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], rust_unlocked: true, rust_keep: vec![RustArtifactEnum::Release], ..Args::default() })]
+    #[case(Args { languages: vec![LanguageEnum::Rust], rust_unlocked: true, rust_max_size: Some(1024), ..Args::default() })]
+    fn test_wipe_params_invalid_rust_unlocked(#[case] args: Args) {
+        let error = WipeParams::new(&args, &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    #[case(Some(0), None)]
+    #[case(None, Some(0))]
+    #[case(Some(3), Some(2))]
+    fn test_wipe_params_invalid_depth(
+        #[case] min_depth: Option<usize>,
+        #[case] max_depth: Option<usize>,
+    ) {
+        let args = Args {
+            languages: vec![LanguageEnum::Rust],
+            min_depth,
+            max_depth,
+            ..Args::default()
+        };
+
+        let error = WipeParams::new(&args, &Config::default()).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[rstest]
+    #[case(None, None, None)]
+    #[case(Some(2), None, Some("from depth 2"))]
+    #[case(None, Some(3), Some("up to depth 3"))]
+    #[case(Some(2), Some(3), Some("at depths 2 to 3"))]
+    #[case(Some(3), Some(3), Some("at depth 3"))]
+    fn test_depth_formatted(
+        #[case] min_depth: Option<usize>,
+        #[case] max_depth: Option<usize>,
+        #[case] output: Option<&str>,
+    ) {
+        let params = WipeParams {
+            min_depth,
+            max_depth,
+            ..WipeParams::default()
+        };
+
+        assert_eq!(params.depth_formatted().as_deref(), output);
+    }
+
+    #[test]
+    fn test_wipe_params_all() {
+        let args = Args {
+            languages: vec![LanguageEnum::All],
+            ..Args::default()
+        };
+
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
+
+        assert_eq!(
+            params.languages,
+            vec![
+                LanguageEnum::Node,
+                LanguageEnum::Rust,
+                LanguageEnum::Terraform
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wipe_params_paths() {
+        let test_run = TestRun::empty();
+        let root = &test_run.path;
+        let first = root.join("first");
+        let nested = first.join("nested");
+        let second = root.join("second");
+
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+
+        let args = Args {
+            languages: vec![LanguageEnum::Rust],
+            paths: vec![nested.clone(), first.join("..").join("second")],
+            roots: vec![first.clone(), second.clone()],
+            ..Args::default()
+        };
+
+        let params = WipeParams::new(&args, &Config::default()).unwrap();
+
+        assert_eq!(
+            params.paths,
+            vec![
+                second.canonicalize().unwrap(),
+                first.canonicalize().unwrap()
+            ]
+        );
+
+        let args = Args {
+            languages: vec![LanguageEnum::Rust],
+            paths: vec![root.join("missing")],
+            ..Args::default()
+        };
+
+        let err = WipeParams::new(&args, &Config::default()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("missing"));
+    }
 }
@@ -1,14 +1,19 @@
+use std::time::SystemTime;
 use std::{fmt::Display, io};
 
 use yansi::Paint as _;
 
-use crate::command::DirectoryEnum;
-use crate::dir_helpers::DirInfo;
+use crate::command::FormatEnum;
+use crate::dir_helpers::{DirInfo, PathStatus, PathToDelete, ScanError};
+use crate::disk_space::{FsSpace, Space};
+use crate::report::Report;
+use crate::summary::Summary;
 use crate::wipe_params::WipeParams;
 
 pub const SPACING_FILES: usize = 12;
 pub const SPACING_SIZE: usize = 18;
-pub const SPACING_PATH: usize = 9;
+pub const SPACING_LAST_USED: usize = 16;
+pub const SPACING_PATH: usize = 4;
 
 #[derive(Debug)]
 pub struct Writer<'a, W>
@@ -16,31 +21,57 @@ where
     W: io::Write,
 {
     stdout: &'a mut W,
+    now: SystemTime,
+    format: FormatEnum,
+    report: Option<Report>,
 }
 
 impl<'a, W> Writer<'a, W>
 where
     W: io::Write,
 {
-    pub fn new(stdout: &'a mut W) -> Self {
-        Self { stdout }
+    pub fn new(stdout: &'a mut W, format: FormatEnum) -> Self {
+        Self {
+            stdout,
+            now: SystemTime::now(),
+            format,
+            report: None,
+        }
     }
 
     pub fn write_header(&mut self, params: &WipeParams) -> io::Result<()> {
-        let directory: DirectoryEnum = params.language.clone().into();
+        if self.format == FormatEnum::Json {
+            self.report = Some(Report::new(params));
+            return Ok(());
+        }
+
+        let directories = params
+            .directories()
+            .iter()
+            .map(|d| format!(r#""{}""#, d.cyan()))
+            .collect::<Vec<_>>()
+            .join(", ");
 
         let title = if params.wipe {
             "[WIPING]".red()
+        } else if params.max_reclaimable.is_some() {
+            "[CHECK]".yellow()
         } else {
             "[DRY RUN]".green()
         };
         write!(self.stdout, "{}", title.bold())?;
 
+        let depth = params
+            .depth_formatted()
+            .map(|depth| format!(", {}", depth.cyan()))
+            .unwrap_or_default();
+
         writeln!(
             self.stdout,
-            r#" Recursively searching for all "{}" folders in {}..."#,
-            &directory.cyan(),
-            params.path.display().cyan(),
+            " Recursively searching for all {} folders in {}{}...",
+            directories,
+            params.paths_formatted().cyan(),
+            depth,
         )?;
 
         self.stdout.flush()?;
@@ -48,8 +79,17 @@ where
     }
 
     pub fn write_content_header(&mut self) -> io::Result<()> {
+        if self.format == FormatEnum::Json {
+            return Ok(());
+        }
+
         writeln!(self.stdout)?;
-        self.writeln_spaced_line("Files #".cyan(), "Size (MB)".cyan(), "", "Path".cyan())?;
+        self.writeln_spaced_line(
+            "Files #".cyan(),
+            "Size (MB)".cyan(),
+            "Last used".cyan(),
+            "Path".cyan(),
+        )?;
 
         self.stdout.flush()?;
         Ok(())
@@ -57,24 +97,49 @@ where
 
     pub fn write_content_line(
         &mut self,
-        path: &str,
-        dir_info: Result<DirInfo, io::Error>,
-        ignored: bool,
+        path_to_delete: &PathToDelete,
+        status: PathStatus,
         result: Option<io::Error>,
     ) -> io::Result<()> {
-        if let Ok(dir_info) = dir_info {
+        if let Some(report) = &mut self.report {
+            report.add_candidate(path_to_delete, status, &result);
+            return Ok(());
+        }
+
+        if status == PathStatus::Filtered {
+            return Ok(());
+        }
+
+        let path = &path_to_delete.path;
+
+        if let Ok(dir_info) = &path_to_delete.dir_info {
             self.write_spaced_line(
                 dir_info.file_count_formatted(),
                 dir_info.size_formatted_mb(),
-                "",
+                dir_info.last_used_formatted(self.now),
                 path,
             )?;
         } else {
-            self.write_spaced_line("?", "?", "", path)?;
+            self.write_spaced_line("?", "?", "?", path)?;
+        }
+
+        match status {
+            PathStatus::Selected | PathStatus::Filtered => {}
+            PathStatus::Ignored => write!(self.stdout, " {}", "[Ignored]".yellow())?,
+            PathStatus::Protected => write!(self.stdout, " {}", "[Protected]".yellow())?,
+            PathStatus::Symlinked => write!(self.stdout, " {}", "[Symlinked]".yellow())?,
+            PathStatus::Recent => write!(self.stdout, " {}", "[Recent]".yellow())?,
+            PathStatus::Declined => write!(self.stdout, " {}", "[Declined]".yellow())?,
         }
 
-        if ignored {
-            write!(self.stdout, " {}", "[Ignored]".yellow())?;
+        if !path_to_delete.projects.is_empty() {
+            let projects = path_to_delete
+                .projects
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>();
+
+            write!(self.stdout, " [Used by {}]", projects.join(", "))?;
         }
 
         if let Some(e) = result {
@@ -82,76 +147,289 @@ where
         }
 
         writeln!(self.stdout)?;
+
+        // the parts are listed below the folder
+        for part in path_to_delete.parts.iter().flatten() {
+            let part_path = format!("  {}", part.name);
+
+            if let Ok(dir_info) = &part.dir_info {
+                self.writeln_spaced_line(
+                    dir_info.file_count_formatted(),
+                    dir_info.size_formatted_mb(),
+                    dir_info.last_used_formatted(self.now),
+                    part_path,
+                )?;
+            } else {
+                self.writeln_spaced_line("?", "?", "?", part_path)?;
+            }
+        }
+
         self.stdout.flush()?;
 
         Ok(())
     }
 
+    /// Asks whether the folder should be wiped, without ending the line.
+    pub fn write_prompt(&mut self, path_to_delete: &PathToDelete) -> io::Result<()> {
+        let size = path_to_delete
+            .dir_info
+            .as_ref()
+            .map_or(String::from("?"), DirInfo::size_formatted_flex);
+
+        write!(
+            self.stdout,
+            "Wipe {} ({})? {} ",
+            path_to_delete.path.cyan(),
+            size,
+            "[y]es, [n]o, [a]ll, [q]uit:".bold(),
+        )?;
+
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Lists the folders and files that could not be read, if any, which are
+    /// missing from the totals.
+    pub fn write_scan_errors(&mut self, scan_errors: &[ScanError]) -> io::Result<()> {
+        if let Some(report) = &mut self.report {
+            report.scan_errors = scan_errors.iter().map(Into::into).collect();
+            return Ok(());
+        }
+
+        if scan_errors.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.stdout)?;
+        writeln!(self.stdout, "{}", "Could not scan".yellow())?;
+
+        for ScanError { path, error } in scan_errors {
+            writeln!(
+                self.stdout,
+                "{:SPACING_PATH$}{} {}",
+                "",
+                path.display(),
+                format!("[{error}]").red(),
+            )?;
+        }
+
+        self.stdout.flush()?;
+        Ok(())
+    }
+
     pub fn write_summary(
         &mut self,
         params: &WipeParams,
-        wipe_info: &DirInfo,
-        ignore_info: &DirInfo,
-        previous_info: &Option<DirInfo>,
+        summaries: &[Summary],
+        total: &Summary,
     ) -> io::Result<()> {
+        if let Some(report) = &mut self.report {
+            report.summaries = summaries.iter().map(Into::into).collect();
+            report.total = Some(total.into());
+            return Ok(());
+        }
+
         writeln!(self.stdout)?;
 
-        if wipe_info.dir_count > 0 {
-            let previous_info = previous_info.expect("this should never be None");
+        if summaries.len() > 1 {
+            for summary in summaries.iter().filter(|s| s.wipe_info.dir_count > 0) {
+                self.write_summary_block(params, summary)?;
+            }
+        }
 
-            let after = DirInfo {
-                dir_count: previous_info.dir_count - wipe_info.dir_count,
-                file_count: previous_info.file_count - wipe_info.file_count,
-                size: previous_info.size - wipe_info.size,
-            };
+        self.write_summary_block(params, total)?;
+
+        self.stdout.flush()?;
+        Ok(())
+    }
 
+    fn write_summary_block(&mut self, params: &WipeParams, summary: &Summary) -> io::Result<()> {
+        let Summary {
+            title,
+            previous_info,
+            wipe_info,
+            ignore_info,
+            protect_info,
+            symlink_info,
+            recent_info,
+            filter_info,
+            decline_info,
+            trash_info,
+            language_infos,
+        } = summary;
+
+        if wipe_info.dir_count > 0 {
             self.writeln_spaced_line(
                 "Files #".cyan(),
                 "Size".cyan(),
-                "",
-                params.path.display().cyan(),
+                "On disk".cyan(),
+                title.cyan(),
             )?;
 
-            let label = if params.wipe {
-                "Previously"
-            } else {
-                "Currently"
-            };
+            // the previous totals are unknown when the search depth is limited
+            if let Some(previous_info) = previous_info {
+                let label = if params.wipe {
+                    "Previously"
+                } else {
+                    "Currently"
+                };
 
-            self.writeln_spaced_line(
-                previous_info.file_count_formatted(),
-                previous_info.size_formatted_flex(),
-                "",
-                label,
-            )?;
+                self.writeln_spaced_line(
+                    previous_info.file_count_formatted(),
+                    previous_info.size_formatted_flex(),
+                    previous_info.disk_size_formatted_flex(),
+                    label,
+                )?;
+            }
 
             if ignore_info.dir_count > 0 {
                 self.writeln_spaced_line(
                     ignore_info.file_count_formatted().yellow(),
                     ignore_info.size_formatted_flex().yellow(),
-                    "",
+                    ignore_info.disk_size_formatted_flex().yellow(),
                     "Ignored".yellow(),
                 )?;
             }
 
+            if protect_info.dir_count > 0 {
+                self.writeln_spaced_line(
+                    protect_info.file_count_formatted().yellow(),
+                    protect_info.size_formatted_flex().yellow(),
+                    protect_info.disk_size_formatted_flex().yellow(),
+                    "Protected".yellow(),
+                )?;
+            }
+
+            if symlink_info.dir_count > 0 {
+                self.writeln_spaced_line(
+                    symlink_info.file_count_formatted().yellow(),
+                    symlink_info.size_formatted_flex().yellow(),
+                    symlink_info.disk_size_formatted_flex().yellow(),
+                    "Skipped (symlinked)".yellow(),
+                )?;
+            }
+
+            if recent_info.dir_count > 0 {
+                self.writeln_spaced_line(
+                    recent_info.file_count_formatted().yellow(),
+                    recent_info.size_formatted_flex().yellow(),
+                    recent_info.disk_size_formatted_flex().yellow(),
+                    "Skipped (recent)".yellow(),
+                )?;
+            }
+
+            if filter_info.dir_count > 0 {
+                let folders = if filter_info.dir_count == 1 {
+                    "folder"
+                } else {
+                    "folders"
+                };
+
+                self.writeln_spaced_line(
+                    filter_info.file_count_formatted().yellow(),
+                    filter_info.size_formatted_flex().yellow(),
+                    filter_info.disk_size_formatted_flex().yellow(),
+                    format!("Filtered out ({} {folders})", filter_info.dir_count).yellow(),
+                )?;
+            }
+
+            if decline_info.dir_count > 0 {
+                self.writeln_spaced_line(
+                    decline_info.file_count_formatted().yellow(),
+                    decline_info.size_formatted_flex().yellow(),
+                    decline_info.disk_size_formatted_flex().yellow(),
+                    "Declined".yellow(),
+                )?;
+            }
+
             let label = if params.wipe { "Wiped" } else { "Can wipe" };
 
+            if language_infos.len() > 1 {
+                for (language, language_info) in language_infos {
+                    self.writeln_spaced_line(
+                        language_info.file_count_formatted().red(),
+                        language_info.size_formatted_flex().red(),
+                        language_info.disk_size_formatted_flex().red(),
+                        format!("{label} ({language})").red(),
+                    )?;
+                }
+            }
+
             self.writeln_spaced_line(
                 wipe_info.file_count_formatted().red(),
                 wipe_info.size_formatted_flex().red(),
-                "",
+                wipe_info.disk_size_formatted_flex().red(),
                 label.red(),
             )?;
 
-            let label = if params.wipe { "Now" } else { "After wipe" };
+            if trash_info.dir_count > 0 {
+                self.writeln_spaced_line(
+                    trash_info.file_count_formatted().yellow(),
+                    trash_info.size_formatted_flex().yellow(),
+                    trash_info.disk_size_formatted_flex().yellow(),
+                    "Moved to trash".yellow(),
+                )?;
+            }
+
+            if let Some(after) = summary.after() {
+                let label = if params.wipe { "Now" } else { "After wipe" };
 
+                self.writeln_spaced_line(
+                    after.file_count_formatted().green(),
+                    after.size_formatted_flex().green(),
+                    after.disk_size_formatted_flex().green(),
+                    label.green(),
+                )?;
+            }
+
+            writeln!(self.stdout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the measured free space of the filesystems holding the folders to wipe.
+    pub fn write_disk_space(&mut self, params: &WipeParams, spaces: &[FsSpace]) -> io::Result<()> {
+        if let Some(report) = &mut self.report {
+            report.filesystems = spaces.iter().map(Into::into).collect();
+            return Ok(());
+        }
+
+        for space in spaces {
             self.writeln_spaced_line(
-                after.file_count_formatted().green(),
-                after.size_formatted_flex().green(),
+                "Free".cyan(),
+                "Used".cyan(),
+                "Freed".cyan(),
+                format!("Free space on {}", space.mount_point.display()).cyan(),
+            )?;
+
+            let label = if params.wipe {
+                "Before wiping"
+            } else {
+                "Right now"
+            };
+            self.writeln_spaced_line(
+                Self::free_formatted(&space.before),
+                Self::used_formatted(&space.before),
                 "",
-                label.green(),
+                label,
             )?;
 
+            if let (Some(after), Some(freed)) = (space.after, space.freed()) {
+                let sign = if freed < 0 { "-" } else { "" };
+
+                self.writeln_spaced_line(
+                    Self::free_formatted(&after).green(),
+                    Self::used_formatted(&after).green(),
+                    format!(
+                        "{sign}{}",
+                        DirInfo::format_flex(freed.unsigned_abs() as usize)
+                    )
+                    .green(),
+                    "After wiping".green(),
+                )?;
+            }
+
             writeln!(self.stdout)?;
         }
 
@@ -159,15 +437,48 @@ where
         Ok(())
     }
 
-    pub fn write_footer(&mut self, params: &WipeParams, wipe_info: &DirInfo) -> io::Result<()> {
-        if wipe_info.dir_count > 0 {
-            if params.wipe {
+    fn free_formatted(space: &Space) -> String {
+        DirInfo::format_flex(space.free as usize)
+    }
+
+    fn used_formatted(space: &Space) -> String {
+        format!("{:.1}%", space.used_percent())
+    }
+
+    pub fn write_footer(&mut self, params: &WipeParams, total: &Summary) -> io::Result<()> {
+        if self.report.is_some() {
+            return self.write_report();
+        }
+
+        if let Some(max_reclaimable) = params.max_reclaimable {
+            let reclaimable = total.wipe_info.disk_size;
+            let message = format!(
+                "{} can be reclaimed, with a budget of {}.",
+                DirInfo::format_flex(reclaimable),
+                DirInfo::format_flex(max_reclaimable),
+            );
+
+            if reclaimable > max_reclaimable {
+                writeln!(self.stdout, "{} {}", "Over budget!".red(), message)?;
+            } else {
+                writeln!(self.stdout, "{} {}", "Within budget!".green(), message)?;
+            }
+        } else if total.wipe_info.dir_count > 0 {
+            if params.wipe && total.trash_info.dir_count > 0 {
+                writeln!(
+                    self.stdout,
+                    "{} Empty the trash to free up the space.",
+                    "All clear!".green()
+                )?
+            } else if params.wipe {
                 writeln!(self.stdout, "{}", "All clear!".green())?
             } else {
+                let trash = if params.trash { " --trash" } else { "" };
+
                 writeln!(
                     self.stdout,
                     "Run {} to wipe all folders found. {}",
-                    format!("cargo wipe {} -w", params.language).red(),
+                    format!("cargo wipe {} -w{trash}", params.languages_formatted()).red(),
                     "USE WITH CAUTION!".red()
                 )?;
             }
@@ -180,6 +491,43 @@ where
         Ok(())
     }
 
+    /// Ends a run that was stopped with `--strict`, since something could not be scanned.
+    pub fn write_strict_footer(&mut self, scan_errors: &[ScanError]) -> io::Result<()> {
+        if self.report.is_some() {
+            return self.write_report();
+        }
+
+        let paths = if scan_errors.len() == 1 {
+            "path"
+        } else {
+            "paths"
+        };
+
+        writeln!(self.stdout)?;
+        writeln!(
+            self.stdout,
+            "{}",
+            format!(
+                "Could not scan {} {paths}, so nothing was wiped because of --strict.",
+                scan_errors.len()
+            )
+            .red()
+        )?;
+
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    fn write_report(&mut self) -> io::Result<()> {
+        if let Some(report) = &self.report {
+            serde_json::to_writer_pretty(&mut *self.stdout, report)?;
+            writeln!(self.stdout)?;
+            self.stdout.flush()?;
+        }
+
+        Ok(())
+    }
+
     fn write_spaced_line(
         &mut self,
         column_1: impl Display,
@@ -189,7 +537,8 @@ where
     ) -> io::Result<()> {
         write!(
             self.stdout,
-            r#"{column_1:>SPACING_FILES$}{column_2:>SPACING_SIZE$}{column_3:>SPACING_PATH$}{column_4}"#,
+            r#"{column_1:>SPACING_FILES$}{column_2:>SPACING_SIZE$}{column_3:>SPACING_LAST_USED$}{:SPACING_PATH$}{column_4}"#,
+            "",
         )?;
 
         Ok(())
@@ -204,7 +553,8 @@ where
     ) -> io::Result<()> {
         writeln!(
             self.stdout,
-            r#"{column_1:>SPACING_FILES$}{column_2:>SPACING_SIZE$}{column_3:>SPACING_PATH$}{column_4}"#,
+            r#"{column_1:>SPACING_FILES$}{column_2:>SPACING_SIZE$}{column_3:>SPACING_LAST_USED$}{:SPACING_PATH$}{column_4}"#,
+            "",
         )?;
 
         Ok(())
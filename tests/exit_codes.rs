@@ -0,0 +1,28 @@
+use std::process::{Command, Stdio};
+
+use rstest::rstest;
+
+#[rstest]
+#[case(&["rust", "--min-depth", "0"])]
+#[case(&["rust", "--min-depth", "3", "--max-depth", "2"])]
+#[case(&[])]
+#[case(&["node", "--rust-only", "debug"])]
+#[case(&["rust", "--unknown"])]
+#[case(&["rust", "--check"])]
+#[case(&["rust", "--max-reclaimable", "1G"])]
+#[case(&["rust", "--check", "--max-reclaimable", "1G", "--wipe"])]
+#[case(&["rust", "--check", "--max-reclaimable", "1G", "--tui"])]
+#[case(&["rust", "-w", "--interactive", "-p", env!("CARGO_TARGET_TMPDIR")])]
+#[case(&["rust", "--tui", "-p", env!("CARGO_TARGET_TMPDIR")])]
+fn invalid_arguments(#[case] args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-wipe"))
+        .arg("wipe")
+        .args(args)
+        .stdin(Stdio::null())
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2), "{output:?}");
+    assert!(!output.stderr.is_empty());
+}
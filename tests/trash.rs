@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A folder with a name unique to the test process, which is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}-{}", std::process::id()));
+
+        fs::create_dir_all(&path).unwrap();
+
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn run_with_trash() {
+    let temp_dir = TempDir::new("run_with_trash");
+    let project = temp_dir.0.join("project");
+    let target = project.join("target");
+    let data_home = temp_dir.0.join("data");
+
+    fs::create_dir_all(target.join("debug")).unwrap();
+    fs::write(target.join(".rustc_info.json"), "{}").unwrap();
+    fs::write(project.join("Cargo.toml"), "").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-wipe"))
+        .args(["wipe", "rust", "-w", "--trash", "-p"])
+        .arg(&project)
+        .env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env("XDG_DATA_HOME", &data_home)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{output:?}");
+
+    // the folder is moved out of the project, and only the folder
+    assert!(!target.exists());
+    assert!(project.join("Cargo.toml").exists());
+
+    // into the trash, next to the info to restore it
+    let trash = data_home.join("Trash");
+    assert!(trash.join("files/target/.rustc_info.json").exists());
+    assert!(trash.join("files/target/debug").is_dir());
+
+    let info = fs::read_to_string(trash.join("info/target.trashinfo")).unwrap();
+    assert!(info.starts_with("[Trash Info]\n"));
+    assert!(
+        info.lines()
+            .any(|line| line.starts_with("Path=/") && line.ends_with("/project/target")),
+        "{info}"
+    );
+}